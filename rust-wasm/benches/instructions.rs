@@ -0,0 +1,187 @@
+//! Deterministic benchmarks that count instructions and allocations instead of measuring time.
+//! These are stable across runs and machines, so they are useful to compare changes on noisy CI runners.
+//!
+//! Run with `cargo bench --bench instructions` or `cargo bench --bench instructions -- day08`
+#![cfg_attr(target_arch = "wasm32", allow(dead_code))]
+
+use advent_of_code_2025::DAYS;
+use advent_of_code_2025::alloc_profile::{self, AllocStats, CountingAllocator};
+use advent_of_code_2025::download_input;
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Clone, Copy, Default)]
+struct Cost {
+    instructions: Option<u64>,
+    allocations: AllocStats,
+}
+
+impl Cost {
+    /// Cost of a part without the parsing
+    fn without(self, parse: Cost) -> Cost {
+        Cost {
+            instructions: self
+                .instructions
+                .zip(parse.instructions)
+                .map(|(total, parse)| total.saturating_sub(parse)),
+            allocations: self.allocations.without(parse.allocations),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod counter {
+    use perf_event::{Builder, Counter, events::Hardware};
+
+    /// Counts the user space instructions of the current thread
+    pub struct Instructions(Counter);
+
+    impl Instructions {
+        pub fn new() -> Result<Self, String> {
+            Builder::new(Hardware::INSTRUCTIONS)
+                .exclude_kernel(true)
+                .exclude_hv(true)
+                .build()
+                .map(Self)
+                .map_err(|e| e.to_string())
+        }
+
+        pub fn measure(&mut self, f: impl FnOnce()) -> u64 {
+            self.0.reset().expect("Failed to reset counter");
+            self.0.enable().expect("Failed to enable counter");
+            f();
+            self.0.disable().expect("Failed to disable counter");
+            self.0.read().expect("Failed to read counter")
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod counter {
+    pub struct Instructions;
+
+    impl Instructions {
+        pub fn new() -> Result<Self, String> {
+            Err("only supported on Linux".to_string())
+        }
+
+        pub fn measure(&mut self, f: impl FnOnce()) -> u64 {
+            f();
+            0
+        }
+    }
+}
+
+fn measure(counter: &mut Option<counter::Instructions>, f: impl Fn()) -> Cost {
+    // Warm up once, so lazy initialization is not counted
+    f();
+
+    let (instructions, allocations) = alloc_profile::measure(|| match counter {
+        Some(counter) => Some(counter.measure(&f)),
+        None => {
+            f();
+            None
+        }
+    });
+
+    Cost {
+        instructions,
+        allocations,
+    }
+}
+
+fn print_cost(day: u32, stage: &str, cost: Cost) {
+    let instructions = cost.instructions.map_or("-".to_string(), |i| i.to_string());
+    println!(
+        "Day {:2} | {:6} | {:>14} | {:>11} | {:>12} | {:>12}",
+        day,
+        stage,
+        instructions,
+        cost.allocations.allocations,
+        cost.allocations.allocated_bytes,
+        cost.allocations.peak_live_bytes
+    );
+}
+
+fn get_input(day: u32) -> Option<String> {
+    if !download_input::check_if_present(day) {
+        println!("Day {:2} | Downloading Input ...", day);
+        if let Err(e) = download_input::download_input(day) {
+            let err = format!("Day {:2} | Failed to download input", day);
+            println!("{err}: {e}");
+            return None;
+        }
+    }
+
+    match download_input::read_input(day) {
+        Ok(input) => Some(input),
+        Err(e) => {
+            println!("Day {:2} | Failed to read input: {}", day, e);
+            None
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    // Run rayon on this thread only, so the counter sees all the work and the counts are deterministic
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .use_current_thread()
+        .build_global()
+        .expect("Failed to build thread pool");
+
+    let mut counter = match counter::Instructions::new() {
+        Ok(counter) => Some(counter),
+        Err(e) => {
+            println!("Instruction counter not available ({e}), only counting allocations\n");
+            None
+        }
+    };
+
+    // Filter days like criterion: `cargo bench --bench instructions -- day08`
+    let filter: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .collect();
+
+    println!("  Day  | Stage  |  Instructions  | Allocations |    Bytes     |  Peak Bytes");
+    println!("-------+--------+----------------+-------------+--------------+--------------");
+
+    for d in DAYS {
+        let name = format!("day{:02}", d.day);
+        if !filter.is_empty() && !filter.iter().any(|f| name.contains(f.as_str())) {
+            continue;
+        }
+
+        let Some(input) = get_input(d.day) else {
+            println!(
+                "-------+--------+----------------+-------------+--------------+--------------"
+            );
+            continue;
+        };
+        let parse = d.parse.map(|parse| measure(&mut counter, || parse(&input)));
+        if let Some(parse) = parse {
+            print_cost(d.day, "Parse", parse);
+        }
+
+        let parts = [("Part 1", d.part1), ("Part 2", d.part2)];
+        for (stage, part) in parts {
+            let Some(part) = part else {
+                continue;
+            };
+            let total = measure(&mut counter, || {
+                std::hint::black_box(part(&input));
+            });
+            // The parts parse the input themselves, so we subtract that to get the solving cost.
+            // The peak is the one of the whole part, including its parsing.
+            let solve = parse.map_or(total, |parse| total.without(parse));
+            print_cost(d.day, stage, solve);
+        }
+        println!("-------+--------+----------------+-------------+--------------+--------------");
+    }
+}
@@ -0,0 +1,245 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+/// Benchmark times of a single day in milliseconds
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DayTimings {
+    pub combined: Option<f64>,
+    pub part1: Option<f64>,
+    pub part2: Option<f64>,
+}
+
+/// Benchmark times keyed by day number
+pub type Measurements = BTreeMap<u32, DayTimings>;
+
+/// All saved baselines, keyed by their name (usually a commit hash or tag)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BaselineFile {
+    pub baselines: BTreeMap<String, Measurements>,
+}
+
+impl BaselineFile {
+    /// Loads the baselines, a missing file is treated as empty
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse '{}': {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).expect("Baselines are always serializable");
+        std::fs::write(path, json + "\n")
+            .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+    }
+}
+
+#[derive(Deserialize)]
+struct Estimates {
+    mean: Estimate,
+}
+
+#[derive(Deserialize)]
+struct Estimate {
+    point_estimate: f64,
+}
+
+/// Reads the latest `dayXX_combined` and `dayXX_seperate` results from criterion's output directory
+pub fn read_criterion(criterion_dir: &Path) -> Result<Measurements, String> {
+    let entries = std::fs::read_dir(criterion_dir).map_err(|e| {
+        format!(
+            "Failed to read '{}', run 'cargo bench' first: {}",
+            criterion_dir.display(),
+            e
+        )
+    })?;
+
+    let mut measurements = Measurements::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some((day, kind)) = name
+            .strip_prefix("day")
+            .and_then(|rest| rest.split_once('_'))
+            .and_then(|(day, kind)| Some((day.parse::<u32>().ok()?, kind)))
+        else {
+            continue;
+        };
+
+        let timings = measurements.entry(day).or_default();
+        match kind {
+            "combined" => timings.combined = Some(read_estimate(&entry.path())?),
+            "seperate" => {
+                timings.part1 = read_estimate(&entry.path().join("Part 1")).ok();
+                timings.part2 = read_estimate(&entry.path().join("Part 2")).ok();
+            }
+            _ => {}
+        }
+    }
+    Ok(measurements)
+}
+
+fn read_estimate(bench_dir: &Path) -> Result<f64, String> {
+    let path = bench_dir.join("new").join("estimates.json");
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    let estimates: Estimates = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse '{}': {}", path.display(), e))?;
+
+    // Criterion reports nanoseconds
+    Ok(estimates.mean.point_estimate / 1_000_000.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    pub day: u32,
+    pub part: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl Comparison {
+    /// Relative change in percent, positive means slower. `None` if the baseline is zero.
+    pub fn change(&self) -> Option<f64> {
+        (self.baseline > 0.0).then(|| (self.current - self.baseline) / self.baseline * 100.0)
+    }
+
+    pub fn is_regression(&self, threshold_percent: f64) -> bool {
+        self.change()
+            .is_some_and(|change| change > threshold_percent)
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Day {:2} | {:8} | {:10.3} ms | {:10.3} ms | ",
+            self.day, self.part, self.baseline, self.current
+        )?;
+        match self.change() {
+            Some(change) => write!(f, "{change:+7.1} %"),
+            None => write!(f, "    n/a"),
+        }
+    }
+}
+
+/// A timing of the baseline that the current run doesn't have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Missing {
+    pub day: u32,
+    pub part: &'static str,
+}
+
+impl fmt::Display for Missing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Day {:2} | {:8} | missing from the current run",
+            self.day, self.part
+        )
+    }
+}
+
+/// Compares every timing of the baseline with the current run. The ones that the current run
+/// doesn't have are returned separately.
+pub fn compare(baseline: &Measurements, current: &Measurements) -> (Vec<Comparison>, Vec<Missing>) {
+    let mut comparisons = Vec::new();
+    let mut missing = Vec::new();
+    for (&day, old) in baseline {
+        let new = current.get(&day).copied().unwrap_or_default();
+        let parts = [
+            ("Combined", old.combined, new.combined),
+            ("Part 1", old.part1, new.part1),
+            ("Part 2", old.part2, new.part2),
+        ];
+        for (part, old, new) in parts {
+            match (old, new) {
+                (Some(baseline), Some(current)) => comparisons.push(Comparison {
+                    day,
+                    part,
+                    baseline,
+                    current,
+                }),
+                (Some(_), None) => missing.push(Missing { day, part }),
+                (None, _) => {}
+            }
+        }
+    }
+    (comparisons, missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings(combined: f64, part1: f64, part2: Option<f64>) -> DayTimings {
+        DayTimings {
+            combined: Some(combined),
+            part1: Some(part1),
+            part2,
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = Measurements::from([
+            (1, timings(1.0, 0.5, Some(0.5))),
+            (2, timings(2.0, 1.0, None)),
+        ]);
+        let current = Measurements::from([
+            (1, timings(1.05, 0.3, Some(0.75))),
+            (3, timings(1.0, 1.0, None)),
+        ]);
+
+        let (comparisons, missing) = compare(&baseline, &current);
+        assert_eq!(comparisons.len(), 3);
+        assert!((comparisons[0].change().unwrap() - 5.0).abs() < 1e-9);
+        assert!(!comparisons[0].is_regression(10.0));
+        assert!(comparisons[0].is_regression(1.0));
+        assert!(!comparisons[1].is_regression(0.0));
+        assert_eq!(comparisons[2].part, "Part 2");
+        assert!(comparisons[2].is_regression(10.0));
+
+        // Day 2 is not in the current run
+        assert_eq!(
+            missing,
+            vec![
+                Missing {
+                    day: 2,
+                    part: "Combined"
+                },
+                Missing {
+                    day: 2,
+                    part: "Part 1"
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zero_baseline() {
+        let baseline = Measurements::from([(1, timings(0.0, 0.0, None))]);
+        let current = Measurements::from([(1, timings(1.0, 0.0, None))]);
+        let (comparisons, _) = compare(&baseline, &current);
+        assert_eq!(comparisons[0].change(), None);
+        assert!(!comparisons[0].is_regression(0.0));
+        assert!(comparisons[0].to_string().ends_with("n/a"));
+    }
+
+    #[test]
+    fn test_baseline_roundtrip() {
+        let mut file = BaselineFile::default();
+        file.baselines.insert(
+            "abc123".to_string(),
+            Measurements::from([(8, timings(39.3, 20.1, Some(19.2)))]),
+        );
+
+        let json = serde_json::to_string(&file).unwrap();
+        let loaded: BaselineFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.baselines, file.baselines);
+    }
+}
@@ -0,0 +1,324 @@
+//! Knuth's Algorithm X with Dancing Links, for exact cover problems like packing the presents of
+//! day 12.
+//!
+//! Primary columns have to be covered exactly `need` times, secondary columns at most once. A
+//! primary column that needs more than one row is how identical pieces are handled: the rows of
+//! the column are only tried in increasing order, so the same set of rows is never searched in a
+//! different order.
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    up: usize,
+    down: usize,
+    left: usize,
+    right: usize,
+    column: usize,
+    row: usize,
+}
+
+pub struct DancingLinks {
+    /// Node 0 is the root, nodes `1..=columns` are the column headers, then the rows follow
+    nodes: Vec<Node>,
+    /// Number of rows left in each column, by header node
+    len: Vec<usize>,
+    /// How often each column still has to be covered, by header node
+    need: Vec<usize>,
+    rows: usize,
+    /// Search steps left before giving up
+    budget: u64,
+}
+
+const ROOT: usize = 0;
+
+impl DancingLinks {
+    /// The primary columns are `0..primary.len()`, each has to be covered `primary[i]` times.
+    /// The secondary columns follow them.
+    pub fn new(primary: &[usize], secondary: usize) -> Self {
+        let columns = primary.len() + secondary;
+        let mut nodes: Vec<Node> = (0..=columns)
+            .map(|i| Node {
+                up: i,
+                down: i,
+                left: i,
+                right: i,
+                column: i,
+                row: usize::MAX,
+            })
+            .collect();
+        // Only primary columns with something to cover are in the list of the root
+        let mut last = ROOT;
+        for (c, _) in primary.iter().enumerate().filter(|(_, need)| **need > 0) {
+            let header = c + 1;
+            nodes[header].left = last;
+            nodes[last].right = header;
+            last = header;
+        }
+        nodes[last].right = ROOT;
+        nodes[ROOT].left = last;
+
+        let mut need = vec![0; columns + 1];
+        need[1..=primary.len()].copy_from_slice(primary);
+        need[primary.len() + 1..].fill(1);
+        DancingLinks {
+            nodes,
+            len: vec![0; columns + 1],
+            need,
+            rows: 0,
+            budget: u64::MAX,
+        }
+    }
+
+    /// Adds a row that covers the given columns, returns its index
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        let row = self.rows;
+        self.rows += 1;
+        let first = self.nodes.len();
+        for (i, &column) in columns.iter().enumerate() {
+            let header = column + 1;
+            assert!(header < self.len.len(), "Column {column} doesn't exist");
+            let node = self.nodes.len();
+            let up = self.nodes[header].up;
+            self.nodes.push(Node {
+                up,
+                down: header,
+                left: if i == 0 { node } else { node - 1 },
+                right: first,
+                column: header,
+                row,
+            });
+            self.nodes[up].down = node;
+            self.nodes[header].up = node;
+            self.nodes[node - usize::from(i > 0)].right = node;
+            self.nodes[first].left = node;
+            self.len[header] += 1;
+        }
+        row
+    }
+
+    /// Gives up after `steps` rows were tried
+    pub fn with_limit(mut self, steps: u64) -> Self {
+        self.budget = steps;
+        self
+    }
+
+    /// The rows of the first solution, `None` if there is none. Fails if the step limit is reached
+    /// first.
+    pub fn solve(&mut self) -> Result<Option<Vec<usize>>, String> {
+        let mut chosen = Vec::new();
+        if self.search(&mut chosen)? {
+            let mut rows: Vec<usize> = chosen.iter().map(|&node| self.nodes[node].row).collect();
+            rows.sort_unstable();
+            Ok(Some(rows))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn search(&mut self, chosen: &mut Vec<usize>) -> Result<bool, String> {
+        // The primary column with the fewest rows left
+        let mut column = self.nodes[ROOT].right;
+        if column == ROOT {
+            return Ok(true);
+        }
+        let mut c = column;
+        while c != ROOT {
+            if self.len[c] < self.len[column] {
+                column = c;
+            }
+            c = self.nodes[c].right;
+        }
+
+        // Every row that was tried is left out for the following ones
+        let mut tried = Vec::new();
+        let mut found = false;
+        while self.len[column] >= self.need[column] {
+            let row = self.nodes[column].down;
+            if self.budget == 0 {
+                self.restore(&tried);
+                return Err("The search took too many steps".to_string());
+            }
+            self.budget -= 1;
+
+            self.select(row);
+            chosen.push(row);
+            let result = self.search(chosen);
+            self.unselect(row);
+            match result {
+                Ok(true) => {
+                    found = true;
+                    break;
+                }
+                Ok(false) => {
+                    chosen.pop();
+                }
+                Err(e) => {
+                    self.restore(&tried);
+                    return Err(e);
+                }
+            }
+            self.unlink_row(row);
+            tried.push(row);
+        }
+        self.restore(&tried);
+        Ok(found)
+    }
+
+    fn restore(&mut self, tried: &[usize]) {
+        for &row in tried.iter().rev() {
+            self.relink_row(row);
+        }
+    }
+
+    /// Takes the row of `node` into the solution
+    fn select(&mut self, node: usize) {
+        self.unlink_row(node);
+        let mut x = node;
+        loop {
+            let column = self.nodes[x].column;
+            self.need[column] -= 1;
+            if self.need[column] == 0 {
+                self.cover(column);
+            }
+            x = self.nodes[x].right;
+            if x == node {
+                break;
+            }
+        }
+    }
+
+    fn unselect(&mut self, node: usize) {
+        let mut x = self.nodes[node].left;
+        loop {
+            let column = self.nodes[x].column;
+            if self.need[column] == 0 {
+                self.uncover(column);
+            }
+            self.need[column] += 1;
+            if x == node {
+                break;
+            }
+            x = self.nodes[x].left;
+        }
+        self.relink_row(node);
+    }
+
+    /// Removes the column and every row in it
+    fn cover(&mut self, column: usize) {
+        let Node { left, right, .. } = self.nodes[column];
+        self.nodes[left].right = right;
+        self.nodes[right].left = left;
+        let mut i = self.nodes[column].down;
+        while i != column {
+            self.hide(i);
+            i = self.nodes[i].down;
+        }
+    }
+
+    fn uncover(&mut self, column: usize) {
+        let mut i = self.nodes[column].up;
+        while i != column {
+            self.unhide(i);
+            i = self.nodes[i].up;
+        }
+        let Node { left, right, .. } = self.nodes[column];
+        self.nodes[left].right = column;
+        self.nodes[right].left = column;
+    }
+
+    /// Removes the other nodes of the row from their columns
+    fn hide(&mut self, node: usize) {
+        let mut x = self.nodes[node].right;
+        while x != node {
+            self.unlink(x);
+            x = self.nodes[x].right;
+        }
+    }
+
+    fn unhide(&mut self, node: usize) {
+        let mut x = self.nodes[node].left;
+        while x != node {
+            self.relink(x);
+            x = self.nodes[x].left;
+        }
+    }
+
+    /// Removes the whole row from its columns
+    fn unlink_row(&mut self, node: usize) {
+        self.unlink(node);
+        self.hide(node);
+    }
+
+    fn relink_row(&mut self, node: usize) {
+        self.unhide(node);
+        self.relink(node);
+    }
+
+    fn unlink(&mut self, x: usize) {
+        let Node {
+            up, down, column, ..
+        } = self.nodes[x];
+        self.nodes[up].down = down;
+        self.nodes[down].up = up;
+        self.len[column] -= 1;
+    }
+
+    fn relink(&mut self, x: usize) {
+        let Node {
+            up, down, column, ..
+        } = self.nodes[x];
+        self.nodes[up].down = x;
+        self.nodes[down].up = x;
+        self.len[column] += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_cover() {
+        // Knuth's example, the only solution is rows 0, 3 and 4
+        let mut links = DancingLinks::new(&[1; 7], 0);
+        for row in [
+            [2, 4, 5].as_slice(),
+            &[0, 3, 6],
+            &[1, 2, 5],
+            &[0, 3],
+            &[1, 6],
+            &[3, 4, 6],
+        ] {
+            links.add_row(row);
+        }
+        assert_eq!(links.solve(), Ok(Some(vec![0, 3, 4])));
+    }
+
+    #[test]
+    fn test_secondary_and_multiplicity() {
+        // Two dominoes on a row of 3 cells don't fit, one does
+        let dominoes = |count: usize| {
+            let mut links = DancingLinks::new(&[count], 3);
+            links.add_row(&[0, 1, 2]);
+            links.add_row(&[0, 2, 3]);
+            links
+        };
+        assert_eq!(dominoes(1).solve(), Ok(Some(vec![0])));
+        assert_eq!(dominoes(2).solve(), Ok(None));
+
+        // Two dominoes on 4 cells
+        let mut links = DancingLinks::new(&[2], 4);
+        for cell in 0..3 {
+            links.add_row(&[0, cell + 1, cell + 2]);
+        }
+        assert_eq!(links.solve(), Ok(Some(vec![0, 2])));
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut links = DancingLinks::new(&[2], 4).with_limit(1);
+        for cell in 0..3 {
+            links.add_row(&[0, cell + 1, cell + 2]);
+        }
+        assert!(links.solve().is_err());
+    }
+}
@@ -4,7 +4,8 @@ crate::solution!(
     "I calculate the number of revolutions based on the modulo of the position, so no iteration is needed.",
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "L68
@@ -21,63 +22,56 @@ L82
 
 use nom::{
     IResult, Parser,
-    character::complete::{self, line_ending, one_of},
-    multi::separated_list1,
+    character::complete::{self, one_of},
 };
 
-pub fn solve_a(input: &str) -> u32 {
-    let (rest, directions) = parse_a(input).expect("Failed to parse input");
-    assert!(rest.is_empty(), "Unparsed input remaining: {rest}");
+use crate::parsing::{lines, parse_all};
 
-    let mut pos: i32 = 50;
+/// The dial has 100 positions. The distances go up to `u32::MAX`, so the positions are `i64`.
+const DIAL: i64 = 100;
+
+pub fn solve_a(input: &str) -> Result<u32, String> {
+    let directions = parse_all(parse, input)?;
+
+    let mut pos: i64 = 50;
     let mut times_at_zero = 0;
     for direction in directions {
-        match direction {
-            Direction::Left(dist) => pos -= dist as i32,
-            Direction::Right(dist) => pos += dist as i32,
+        pos = match direction {
+            Direction::Left(dist) => pos - dist as i64,
+            Direction::Right(dist) => pos + dist as i64,
         }
-
-        // Wrap around at 100
-        pos = (pos + 100) % 100;
+        .rem_euclid(DIAL);
 
         if pos == 0 {
             times_at_zero += 1;
         }
     }
-    times_at_zero
+    Ok(times_at_zero)
 }
 
-pub fn solve_b(input: &str) -> u32 {
-    let (rest, directions) = parse_a(input).expect("Failed to parse input");
-    assert!(rest.is_empty(), "Unparsed input remaining: {rest}");
+pub fn solve_b(input: &str) -> Result<u64, String> {
+    let directions = parse_all(parse, input)?;
 
-    let mut pos: i32 = 50;
+    let mut pos: i64 = 50;
     let mut times_at_zero = 0;
     for direction in directions {
-        match direction {
-            Direction::Left(dist) => {
-                if dist as i32 >= pos {
-                    times_at_zero += (((dist + 100) as i32 - pos) as f64 / 100.0).floor() as u32;
-                    if pos == 0 {
-                        times_at_zero -= 1;
-                    }
-                }
-                pos -= dist as i32;
-            }
-            Direction::Right(dist) => {
-                if (pos + dist as i32) >= 100 {
-                    times_at_zero += ((pos + dist as i32) as f64 / 100.0).floor() as u32;
-                }
-                pos += dist as i32;
-            }
+        times_at_zero += match direction {
+            // Turning left from `pos` passes zero as often as turning right from the mirrored
+            // position does
+            Direction::Left(dist) => ((DIAL - pos) % DIAL + dist as i64).div_euclid(DIAL),
+            Direction::Right(dist) => (pos + dist as i64).div_euclid(DIAL),
+        } as u64;
+        pos = match direction {
+            Direction::Left(dist) => pos - dist as i64,
+            Direction::Right(dist) => pos + dist as i64,
         }
-        pos = (pos + 10_000_000) % 100;
+        .rem_euclid(DIAL);
     }
-    times_at_zero
+    Ok(times_at_zero)
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum Direction {
+pub enum Direction {
     Left(u32),
     Right(u32),
 }
@@ -93,71 +87,123 @@ fn parse_direction(input: &str) -> IResult<&str, Direction> {
     Ok((input, direction))
 }
 
-fn parse_a(input: &str) -> IResult<&str, Vec<Direction>> {
-    separated_list1(line_ending, parse_direction).parse(input.trim())
+pub fn parse(input: &str) -> IResult<&str, Vec<Direction>> {
+    lines(parse_direction).parse(input.trim())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
 
     #[test]
     fn test_parse_a() {
-        let (remaining, directions) = parse_a(EXAMPLE).expect("Failed to parse directions");
+        let (remaining, directions) = parse(EXAMPLE).expect("Failed to parse directions");
         assert_eq!(remaining, "");
         assert_eq!(directions.len(), 10);
     }
 
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            solve_a("L68\nL30\nX48"),
+            Err("Failed to parse input at line 3, column 1: unexpected 'X'".to_string())
+        );
+    }
+
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 3);
+        assert_eq!(solve_a(EXAMPLE), Ok(3));
     }
 
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 6);
+        assert_eq!(solve_b(EXAMPLE), Ok(6));
     }
 
     #[test]
     fn test_wrap_around() {
         let input = "R1000";
-        assert_eq!(solve_b(input), 10);
+        assert_eq!(solve_b(input), Ok(10));
     }
 
     #[test]
     fn test_wrap_around2() {
         let input = "L1000";
-        assert_eq!(solve_b(input), 10);
+        assert_eq!(solve_b(input), Ok(10));
     }
 
     #[test]
     fn test_wrap_around3() {
         let input = "R150";
-        assert_eq!(solve_b(input), 2);
+        assert_eq!(solve_b(input), Ok(2));
         let input = "R150\nL150";
-        assert_eq!(solve_b(input), 3);
+        assert_eq!(solve_b(input), Ok(3));
         let input = "R150\nL200";
-        assert_eq!(solve_b(input), 4);
+        assert_eq!(solve_b(input), Ok(4));
         let input = "R150\nL210";
-        assert_eq!(solve_b(input), 4);
+        assert_eq!(solve_b(input), Ok(4));
         let input = "R151";
-        assert_eq!(solve_b(input), 2);
+        assert_eq!(solve_b(input), Ok(2));
         let input = "R151\nL200";
-        assert_eq!(solve_b(input), 4);
+        assert_eq!(solve_b(input), Ok(4));
         let input = "R151\nL220";
-        assert_eq!(solve_b(input), 5);
+        assert_eq!(solve_b(input), Ok(5));
     }
 
     #[test]
     fn edge_case_zero_start() {
         let input = "L50";
-        assert_eq!(solve_b(input), 1);
+        assert_eq!(solve_b(input), Ok(1));
         let input = "R50";
-        assert_eq!(solve_b(input), 1);
+        assert_eq!(solve_b(input), Ok(1));
+
+        assert_eq!(solve_b("L50\nL100"), Ok(2));
+        assert_eq!(solve_b("R50\nL100"), Ok(2));
+        assert_eq!(solve_b("L50\nR100"), Ok(2));
+        assert_eq!(solve_b("R50\nR100"), Ok(2));
+    }
+
+    #[test]
+    fn test_huge_distances() {
+        let input = include_str!("../fuzz/regressions/day01/huge-distances");
+        assert_eq!(solve_a(input), Ok(0));
+        // Ends at 97, 2 and 97, passing zero 21474836, 42949672 and 42949672 times
+        assert_eq!(solve_b(input), Ok(21474836 + 42949672 + 42949672));
+    }
 
-        assert_eq!(solve_b("L50\nL100"), 2);
-        assert_eq!(solve_b("R50\nL100"), 2);
-        assert_eq!(solve_b("L50\nR100"), 2);
-        assert_eq!(solve_b("R50\nR100"), 2);
+    /// Turns the dial one click at a time
+    fn reference(input: &str) -> (u32, u64) {
+        let (mut pos, mut end_at_zero, mut clicks_at_zero) = (50, 0, 0);
+        for line in input.lines() {
+            let (direction, distance) = line.split_at(1);
+            let step = if direction == "L" { 99 } else { 1 };
+            for _ in 0..distance.parse::<u32>().unwrap() {
+                pos = (pos + step) % 100;
+                if pos == 0 {
+                    clicks_at_zero += 1;
+                }
+            }
+            if pos == 0 {
+                end_at_zero += 1;
+            }
+        }
+        (end_at_zero, clicks_at_zero)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_matches_reference(rotations in prop::collection::vec((any::<bool>(), 1u32..1000), 1..50)) {
+            let input = rotations
+                .iter()
+                .map(|&(left, distance)| format!("{}{}", if left { 'L' } else { 'R' }, distance))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let (a, b) = reference(&input);
+            prop_assert_eq!(solve_a(&input), Ok(a));
+            prop_assert_eq!(solve_b(&input), Ok(b));
+        }
     }
 }
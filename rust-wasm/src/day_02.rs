@@ -9,7 +9,8 @@ crate::solution!(
     ",
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,
@@ -21,39 +22,40 @@ use std::{collections::HashSet, ops::Range};
 use nom::{
     IResult, Parser,
     branch::alt,
-    bytes::tag,
-    character::complete::{self, multispace1},
+    bytes::complete::tag,
+    character::complete::multispace1,
     multi::{many1, separated_list1},
 };
 
+use crate::interval_set::IntervalSet;
+use crate::parsing::{id_range, parse_all};
+
+/// Merging the ranges first makes sure overlapping ranges don't count an ID twice
+fn parse_ids(input: &str) -> Result<IntervalSet<u64>, String> {
+    Ok(parse_all(parse, input)?.into_iter().collect())
+}
+
 // Genereate all the valid ids and then check the ranges
-pub fn solve_a(input: &str) -> u64 {
-    let (remaining, ranges) = parse(input).expect("Failed to parse ranges");
-    debug_assert!(
-        remaining.is_empty(),
-        "Unparsed input remaining: {}",
-        remaining
-    );
-
-    ranges
+pub fn solve_a(input: &str) -> Result<u128, String> {
+    let ids = parse_ids(input)?;
+
+    // Made up inputs can add up to more than u64::MAX
+    Ok(ids
+        .ranges()
         .iter()
-        .flat_map(move |range| invalid_a(range))
-        .sum::<u64>()
+        .flat_map(invalid_a)
+        .map(u128::from)
+        .sum::<u128>())
 }
 
-pub fn solve_b(input: &str) -> u64 {
-    let (remaining, ranges) = parse(input).expect("Failed to parse ranges");
-    debug_assert!(
-        remaining.is_empty(),
-        "Unparsed input remaining: {}",
-        remaining
-    );
+pub fn solve_b(input: &str) -> Result<u128, String> {
+    let ids = parse_ids(input)?;
 
-    let max_digits = ranges
-        .iter()
-        .map(|range| range.end)
-        .max()
-        .unwrap_or(0)
+    // The ranges are sorted, so the last one ends with the largest ID
+    let max_digits = ids
+        .ranges()
+        .last()
+        .map_or(0, |range| range.end)
         .to_string()
         .len();
 
@@ -61,13 +63,13 @@ pub fn solve_b(input: &str) -> u64 {
     // Like [11][11][11] and [1][1][1][1][1][1][1] or [111][111]
     let set = (2..=max_digits)
         .flat_map(|repitions| {
-            ranges
+            ids.ranges()
                 .iter()
                 .flat_map(move |range| invalid_b(range, repitions))
         })
         .collect::<HashSet<u64>>();
 
-    set.into_iter().sum::<u64>()
+    Ok(set.into_iter().map(u128::from).sum::<u128>())
 }
 
 /// N = base * (10^d + 1)
@@ -77,17 +79,15 @@ fn invalid_a(range: &Range<u64>) -> impl Iterator<Item = u64> {
         // We calculate the multiplier for the current number of digits
         let multiplier = 10u64.pow(num_digits) + 1;
 
-        // Calculate which bases produce numbers in [start, end]
+        // Calculate which bases produce numbers in [start, end)
         let min_base = range.start.div_ceil(multiplier);
-        let max_base = range.end / multiplier;
+        let max_base = (range.end - 1) / multiplier;
 
         // Base must have exactly num_digits
         let base_start = 10u64.pow(num_digits - 1);
         let base_end = 10u64.pow(num_digits);
 
-        (min_base.max(base_start)..=max_base.min(base_end - 1))
-            .map(move |base| base * multiplier)
-            .filter(move |&n| n >= range.start && n <= range.end)
+        (min_base.max(base_start)..=max_base.min(base_end - 1)).map(move |base| base * multiplier)
     })
 }
 
@@ -108,45 +108,28 @@ fn invalid_b(range: &Range<u64>, repitions: usize) -> impl Iterator<Item = u64>
         let d = pattern_len as u32;
         let power_d = 10u64.pow(d);
 
-        // Calculate the geometric series sum multiplier
-        let multiplier = if let Some(power_kd) = 10u64.checked_pow(repitions as u32 * d) {
-            (power_kd - 1) / (power_d - 1)
-        } else {
-            return (0..0)
-                .filter_map(|_| None::<u64>)
-                .collect::<Vec<_>>()
-                .into_iter();
-        };
+        // Calculate the geometric series sum multiplier. If it doesn't fit, no base fits either,
+        // and u64::MAX leaves the range of bases empty.
+        let multiplier = 10u64
+            .checked_pow(repitions as u32 * d)
+            .map_or(u64::MAX, |power_kd| (power_kd - 1) / (power_d - 1));
 
-        (base_start..base_end)
-            .filter_map(move |base| {
-                base.checked_mul(multiplier)
-                    .filter(|&n| n >= range.start && n <= range.end)
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
+        // Only the bases that produce numbers in [start, end)
+        let min_base = range.start.div_ceil(multiplier).max(base_start);
+        let max_base = ((range.end - 1) / multiplier).min(base_end - 1);
+        (min_base..=max_base).map(move |base| base * multiplier)
     })
 }
 
 pub fn parse(input: &str) -> IResult<&str, Vec<Range<u64>>> {
-    fn id_pair(input: &str) -> IResult<&str, Range<u64>> {
-        let (input, start) = complete::u64(input)?;
-        let (input, _) = complete::char('-')(input)?;
-        let (input, end) = complete::u64(input)?;
-        Ok((
-            input,
-            Range {
-                start,
-                end: end + 1,
-            },
-        ))
-    }
-    separated_list1(many1(alt((tag(","), multispace1))), id_pair).parse_complete(input.trim())
+    separated_list1(many1(alt((tag(","), multispace1))), id_range).parse(input.trim())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
 
     #[test]
     fn test_parse_a() {
@@ -172,11 +155,72 @@ mod tests {
 
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 1227775554);
+        assert_eq!(solve_a(EXAMPLE), Ok(1227775554));
     }
 
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 4174379265);
+        assert_eq!(solve_b(EXAMPLE), Ok(4174379265));
+    }
+
+    #[test]
+    fn test_range_end_is_inclusive() {
+        // 22 is just outside the range
+        assert_eq!(solve_a("10-21"), Ok(11));
+        assert_eq!(solve_b("10-21"), Ok(11));
+        // The exclusive end would not fit into a u64
+        assert!(parse("1-18446744073709551615").is_err());
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        assert_eq!(solve_a("10-30,20-40"), Ok(11 + 22 + 33));
+        assert_eq!(solve_b("100-120,110-115,111-111"), Ok(111));
+    }
+
+    /// Checks every ID in the ranges by comparing its digits
+    fn reference(input: &str) -> (u64, u64) {
+        let is_repeated = |digits: &[u8], times: usize| {
+            digits.len().is_multiple_of(times)
+                && digits
+                    .chunks(digits.len() / times)
+                    .all(|chunk| chunk == &digits[..digits.len() / times])
+        };
+
+        let (mut a, mut b) = (0, 0);
+        for range in input.split(',') {
+            let (start, end) = range.split_once('-').unwrap();
+            for id in start.parse::<u64>().unwrap()..=end.parse().unwrap() {
+                let digits = id.to_string().into_bytes();
+                if is_repeated(&digits, 2) {
+                    a += id;
+                }
+                if (2..=digits.len()).any(|times| is_repeated(&digits, times)) {
+                    b += id;
+                }
+            }
+        }
+        (a, b)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_matches_reference(ranges in prop::collection::vec((0u64..5_000_000, 0u64..2_000), 1..10)) {
+            // Build non overlapping ranges, the puzzle input never overlaps
+            let mut cursor = 1;
+            let input = ranges
+                .iter()
+                .map(|&(gap, width)| {
+                    let start = cursor + gap;
+                    cursor = start + width + 1;
+                    format!("{}-{}", start, start + width)
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            let (a, b) = reference(&input);
+            prop_assert_eq!(solve_a(&input), Ok(u128::from(a)));
+            prop_assert_eq!(solve_b(&input), Ok(u128::from(b)));
+        }
     }
 }
@@ -4,7 +4,8 @@ crate::solution!(
     r"If you look at any prefix of the input that excludes the last $n \in \{2, 12\}$ digits, the largest digit in that prefix is the first digit of the answer. This lets you greedily solve the problem, and array slicing makes the implementation efficient.",
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "987654321111111
@@ -14,35 +15,36 @@ static EXAMPLE: &str = "987654321111111
 
 use nom::{
     IResult, Parser,
-    character::complete::{multispace1, one_of},
-    multi::{many1, separated_list1},
+    character::complete::one_of,
+    combinator::verify,
+    multi::many1,
 };
 
+use crate::parsing::{parse_all, whitespace_separated};
+
+/// Every bank needs at least 2 batteries, so part 1 can turn on two of them
 pub fn parse(input: &str) -> IResult<&str, Vec<Vec<u8>>> {
     let bank = many1(one_of("123456789").map(|d| d.to_digit(10).unwrap() as u8));
-    separated_list1(multispace1, bank).parse(input.trim())
+    let bank = verify(bank, |bank: &Vec<u8>| bank.len() >= 2);
+    whitespace_separated(bank).parse(input.trim())
 }
 
 /// Largest number is always involved in the solution
-pub fn solve_a(input: &str) -> u64 {
-    let (rest, banks) = parse(input).expect("Failed to parse input");
-    assert!(rest.is_empty(), "Unparsed input remaining");
+pub fn solve_a(input: &str) -> Result<u64, String> {
+    let banks = parse_all(parse, input)?;
 
-    banks.iter().map(|bank| find_max_joltage(bank, 2)).sum()
+    Ok(banks.iter().map(|bank| find_max_joltage(bank, 2)).sum())
 }
 
-pub fn solve_b(input: &str) -> u64 {
-    let (rest, banks) = parse(input).expect("Failed to parse input");
-    assert!(rest.is_empty(), "Unparsed input remaining");
+pub fn solve_b(input: &str) -> Result<u64, String> {
+    let banks = parse_all(parse, input)?;
 
-    banks.iter().map(|bank| find_max_joltage(bank, 12)).sum()
+    Ok(banks.iter().map(|bank| find_max_joltage(bank, 12)).sum())
 }
 
+/// Banks with fewer batteries than `limit` turn all of them on
 fn find_max_joltage(bank: &[u8], limit: u32) -> u64 {
-    assert!(
-        bank.len() >= limit as usize,
-        "Bank must be at least as long as limit"
-    );
+    let limit = limit.min(bank.len() as u32);
 
     let mut total: u64 = 0;
     let mut last_index = 0;
@@ -72,6 +74,8 @@ fn find_max(input: &[u8]) -> (u8, usize) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
 
     #[test]
     fn test_parse_a() {
@@ -82,46 +86,86 @@ mod tests {
 
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 357);
+        assert_eq!(solve_a(EXAMPLE), Ok(357));
     }
 
     #[test]
     fn test_a_all_same() {
-        assert_eq!(solve_a("222222222222222"), 22);
+        assert_eq!(solve_a("222222222222222"), Ok(22));
     }
 
     #[test]
     fn test_a_increasing() {
-        assert_eq!(solve_a("1234512345"), 55);
+        assert_eq!(solve_a("1234512345"), Ok(55));
     }
 
     #[test]
     fn test_a_other_edges() {
-        assert_eq!(solve_a("9123456789"), 99);
-        assert_eq!(solve_a("9876543211"), 98);
-        assert_eq!(solve_a("999999"), 99);
+        assert_eq!(solve_a("9123456789"), Ok(99));
+        assert_eq!(solve_a("9876543211"), Ok(98));
+        assert_eq!(solve_a("999999"), Ok(99));
     }
 
     #[test]
     fn test_a_end() {
-        assert_eq!(solve_a("1111111119"), 19);
-        assert_eq!(solve_a("1111111199"), 99);
-        assert_eq!(solve_a("1111611189"), 89);
-        assert_eq!(solve_a("1111611181"), 81);
-        assert_eq!(solve_a("1111611162"), 66);
-        assert_eq!(solve_a("1111616162"), 66);
+        assert_eq!(solve_a("1111111119"), Ok(19));
+        assert_eq!(solve_a("1111111199"), Ok(99));
+        assert_eq!(solve_a("1111611189"), Ok(89));
+        assert_eq!(solve_a("1111611181"), Ok(81));
+        assert_eq!(solve_a("1111611162"), Ok(66));
+        assert_eq!(solve_a("1111616162"), Ok(66));
     }
 
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 3121910778619);
+        assert_eq!(solve_b(EXAMPLE), Ok(3121910778619));
+    }
+
+    #[test]
+    fn test_b_short_bank() {
+        assert_eq!(solve_b("91"), Ok(91));
+        assert!(parse("9").is_err());
     }
 
     #[test]
     fn test_b_lines() {
-        assert_eq!(solve_b("987654321111111"), 987654321111);
-        assert_eq!(solve_b("811111111111119"), 811111111119);
-        assert_eq!(solve_b("234234234234278"), 434234234278);
-        assert_eq!(solve_b("818181911112111"), 888911112111);
+        assert_eq!(solve_b("987654321111111"), Ok(987654321111));
+        assert_eq!(solve_b("811111111111119"), Ok(811111111119));
+        assert_eq!(solve_b("234234234234278"), Ok(434234234278));
+        assert_eq!(solve_b("818181911112111"), Ok(888911112111));
+    }
+
+    /// Tries every combination of `count` batteries
+    fn reference(bank: &[u8], count: usize) -> u64 {
+        if count == 0 {
+            return 0;
+        }
+        (0..=bank.len() - count)
+            .map(|i| {
+                u64::from(bank[i]) * 10u64.pow(count as u32 - 1) + reference(&bank[i + 1..], count - 1)
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reference() {
+        assert_eq!(reference(&[8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1], 12), 888911112111);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_matches_reference(banks in prop::collection::vec(prop::collection::vec(1u8..10, 12..16), 1..5)) {
+            let input = banks
+                .iter()
+                .map(|bank| bank.iter().map(|d| d.to_string()).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let a = banks.iter().map(|bank| reference(bank, 2)).sum::<u64>();
+            let b = banks.iter().map(|bank| reference(bank, 12)).sum::<u64>();
+            prop_assert_eq!(solve_a(&input), Ok(a));
+            prop_assert_eq!(solve_b(&input), Ok(b));
+        }
     }
 }
@@ -4,7 +4,8 @@ crate::solution!(
     r"Simple Cellular Automaton Simulation using matrix convolution with a $3 \times 3$ kernel. This could be optimized further by keeping track of recently changed cell neighbours in a queue.",
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "..@@.@@@@.
@@ -20,37 +21,18 @@ static EXAMPLE: &str = "..@@.@@@@.
 
 use ndarray::prelude::*;
 use ndarray_conv::{ConvExt, ConvMode, PaddingMode};
-use nom::{
-    IResult, Parser,
-    character::complete::{multispace1, one_of},
-    multi::{many1, separated_list1},
-};
-
-pub fn parse(input: &str) -> IResult<&str, Vec<Vec<bool>>> {
-    let row = many1(one_of(".@").map(|d| match d {
-        '@' => true,
-        '.' => false,
-        _ => unreachable!(),
-    }));
-    let (rest, grid) = separated_list1(multispace1, row).parse(input.trim())?;
-
-    debug_assert!(rest.is_empty(), "Unparsed input remaining");
-
-    // Test that all rows are same length
-    debug_assert!(
-        grid.iter().all(|row| row.len() == grid[0].len()),
-        "Non-rectangular grid"
-    );
-    Ok((rest, grid))
-}
+use nom::{IResult, Parser};
+
+use crate::grid::Grid;
+use crate::parsing::{cell_grid, parse_all};
 
-pub fn solve_a(input: &str) -> u64 {
-    let (_, grid) = parse(input).expect("Failed to parse input");
-    let rows = grid.len();
-    let cols = grid[0].len();
+pub fn parse(input: &str) -> IResult<&str, Grid<bool>> {
+    cell_grid('@', '.').parse(input.trim())
+}
 
-    // Convert vec to ndarray
-    let matrix = Array2::from_shape_fn((rows, cols), |(r, c)| if grid[r][c] { 1u8 } else { 0u8 });
+pub fn solve_a(input: &str) -> Result<u64, String> {
+    let grid = parse_all(parse, input)?;
+    let matrix = grid.map(|&roll| u8::from(roll)).to_array2();
 
     // Convolution kernel
     let kernel = arr2(&[[1u8, 1u8, 1u8], [1u8, 0u8, 1u8], [1u8, 1u8, 1u8]]);
@@ -62,17 +44,12 @@ pub fn solve_a(input: &str) -> u64 {
     neighbours.mapv_inplace(|x| if x < 4 { 1u8 } else { 0u8 });
     let accessible = matrix * neighbours;
 
-    accessible.iter().map(|&x| u64::from(x)).sum()
+    Ok(accessible.iter().map(|&x| u64::from(x)).sum())
 }
 
-pub fn solve_b(input: &str) -> u64 {
-    let (_, grid) = parse(input).expect("Failed to parse input");
-    let rows = grid.len();
-    let cols = grid[0].len();
-
-    // Convert vec to ndarray
-    let mut matrix =
-        Array2::from_shape_fn((rows, cols), |(r, c)| if grid[r][c] { 1u8 } else { 0u8 });
+pub fn solve_b(input: &str) -> Result<u64, String> {
+    let grid = parse_all(parse, input)?;
+    let mut matrix = grid.map(|&roll| u8::from(roll)).to_array2();
     // Convolution kernel
     let kernel = arr2(&[[1u8, 1u8, 1u8], [1u8, 0u8, 1u8], [1u8, 1u8, 1u8]]);
 
@@ -96,27 +73,77 @@ pub fn solve_b(input: &str) -> u64 {
         count = current_count;
     }
 
-    inital_count - count
+    Ok(inital_count - count)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
 
     #[test]
     fn test_parse_a() {
         let (remaining, parsed) = parse(EXAMPLE).expect("Failed to parse directions");
         assert!(remaining.is_empty(), "Unparsed input remaining");
-        assert!(parsed.len() == 10);
+        assert_eq!((parsed.height(), parsed.width()), (10, 10));
     }
 
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 13);
+        assert_eq!(solve_a(EXAMPLE), Ok(13));
     }
 
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 43);
+        assert_eq!(solve_b(EXAMPLE), Ok(43));
+    }
+
+    /// Counts the neighbours of every roll directly and removes one roll at a time
+    fn reference(grid: &[Vec<bool>]) -> (u64, u64) {
+        let mut grid = grid.to_vec();
+        let accessible = |grid: &[Vec<bool>], y: usize, x: usize| {
+            let neighbours = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dy, dx)))
+                .filter(|&offset| offset != (0, 0))
+                .filter(|&(dy, dx)| {
+                    let (ny, nx) = (y.checked_add_signed(dy), x.checked_add_signed(dx));
+                    ny.zip(nx)
+                        .and_then(|(ny, nx)| grid.get(ny)?.get(nx))
+                        .is_some_and(|&roll| roll)
+                })
+                .count();
+            grid[y][x] && neighbours < 4
+        };
+
+        let (height, width) = (grid.len(), grid[0].len());
+        let cells = || (0..height).flat_map(|y| (0..width).map(move |x| (y, x)));
+        let a = cells().filter(|&(y, x)| accessible(&grid, y, x)).count() as u64;
+
+        let mut b = 0;
+        while let Some((y, x)) = cells().find(|&(y, x)| accessible(&grid, y, x)) {
+            grid[y][x] = false;
+            b += 1;
+        }
+        (a, b)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_matches_reference(
+            grid in (1usize..12, 1usize..12).prop_flat_map(|(height, width)| {
+                prop::collection::vec(prop::collection::vec(any::<bool>(), width), height)
+            })
+        ) {
+            let input = grid
+                .iter()
+                .map(|row| row.iter().map(|&roll| if roll { '@' } else { '.' }).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let (a, b) = reference(&grid);
+            prop_assert_eq!(solve_a(&input), Ok(a));
+            prop_assert_eq!(solve_b(&input), Ok(b));
+        }
     }
 }
@@ -4,7 +4,8 @@ crate::solution!(
     r"For Part 2 we insert the ranges into a new list while merging overlaps, so we don't double count anything",
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "3-5
@@ -22,65 +23,41 @@ static EXAMPLE: &str = "3-5
 use std::ops::Range;
 
 use nom::{
-    IResult, Parser, character::complete::multispace1, multi::separated_list1,
+    IResult, Parser,
+    character::complete::{self, multispace1},
     sequence::separated_pair,
 };
 
+use crate::interval_set::IntervalSet;
+use crate::parsing::{id_range, parse_all, whitespace_separated};
+
 pub fn parse(input: &str) -> IResult<&str, (Vec<Range<u64>>, Vec<u64>)> {
-    let ranges = separated_list1(
-        multispace1,
-        nom::sequence::separated_pair(
-            nom::character::complete::u64,
-            nom::bytes::complete::tag("-"),
-            nom::character::complete::u64,
-        )
-        .map(|(start, end)| start..end + 1),
-    );
-    let ids = separated_list1(multispace1, nom::character::complete::u64);
-
-    let (rest, (ranges, ids)) = separated_pair(ranges, multispace1, ids).parse(input.trim())?;
-
-    debug_assert!(rest.is_empty(), "Unparsed input remaining");
-
-    Ok((rest, (ranges, ids)))
-}
+    let ranges = whitespace_separated(id_range);
+    let ids = whitespace_separated(complete::u64);
 
-pub fn solve_a(input: &str) -> u64 {
-    let (_, (ranges, ids)) = parse(input).expect("Failed to parse input");
+    separated_pair(ranges, multispace1, ids).parse(input.trim())
+}
 
-    let count = ids
-        .iter()
-        .filter(|id| ranges.iter().any(move |r| r.contains(id)))
-        .count();
+pub fn solve_a(input: &str) -> Result<u64, String> {
+    let (ranges, ids) = parse_all(parse, input)?;
+    let fresh: IntervalSet<u64> = ranges.into_iter().collect();
 
-    count as u64
+    let count = fresh.contains_all(&ids).into_iter().filter(|&c| c).count();
+    Ok(count as u64)
 }
 
-pub fn solve_b(input: &str) -> u64 {
-    let (_, (mut ranges, _)) = parse(input).expect("Failed to parse input");
-
-    let mut merged: Vec<Range<u64>> = Vec::new();
-    ranges.sort_by_key(|r| r.start);
-
-    for r in ranges {
-        if let Some(last) = merged.last_mut() {
-            if r.start <= last.end {
-                last.end = last.end.max(r.end);
-            } else {
-                merged.push(r);
-            }
-        } else {
-            merged.push(r);
-        }
-    }
+pub fn solve_b(input: &str) -> Result<u64, String> {
+    let (ranges, _) = parse_all(parse, input)?;
+    let fresh: IntervalSet<u64> = ranges.into_iter().collect();
 
-    let count = merged.iter().map(|r| r.end - r.start).sum();
-    count
+    Ok(fresh.total_len())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
 
     #[test]
     fn test_parse_a() {
@@ -92,12 +69,12 @@ mod tests {
 
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 3);
+        assert_eq!(solve_a(EXAMPLE), Ok(3));
     }
 
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 14);
+        assert_eq!(solve_b(EXAMPLE), Ok(14));
     }
 
     #[test]
@@ -108,6 +85,33 @@ mod tests {
 3-10
 
 1";
-        assert_eq!(solve_b(inp), 8);
+        assert_eq!(solve_b(inp), Ok(8));
+    }
+
+    /// Collects every fresh ID into a set
+    fn reference(ranges: &[(u64, u64)], ids: &[u64]) -> (u64, u64) {
+        let fresh: std::collections::BTreeSet<u64> =
+            ranges.iter().flat_map(|&(start, end)| start..=end).collect();
+        let a = ids.iter().filter(|id| fresh.contains(id)).count() as u64;
+        (a, fresh.len() as u64)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_matches_reference(
+            ranges in prop::collection::vec((0u64..200, 0u64..30), 1..10),
+            ids in prop::collection::vec(0u64..250, 1..20)
+        ) {
+            let ranges: Vec<(u64, u64)> = ranges.iter().map(|&(start, width)| (start, start + width)).collect();
+            let input = format!(
+                "{}\n\n{}",
+                ranges.iter().map(|(start, end)| format!("{start}-{end}")).collect::<Vec<_>>().join("\n"),
+                ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join("\n")
+            );
+            let (a, b) = reference(&ranges, &ids);
+            prop_assert_eq!(solve_a(&input), Ok(a));
+            prop_assert_eq!(solve_b(&input), Ok(b));
+        }
     }
 }
@@ -4,7 +4,8 @@ crate::solution!(
     r"Parsing made Part 2 difficult",
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "123 328  51 64 
@@ -12,21 +13,25 @@ static EXAMPLE: &str = "123 328  51 64
   6 98  215 314
 *   +   *   +  ";
 
-use ndarray::Array2;
 use nom::{
     IResult, Parser,
     branch::alt,
     bytes::complete::tag,
     character::complete::{multispace0, multispace1, space1},
+    combinator::verify,
     multi::separated_list1,
     sequence::separated_pair,
 };
 
+use crate::grid::Grid;
+use crate::parsing::parse_all;
+
 pub enum Operation {
     Add,
     Multiply,
 }
 
+/// Every row needs a number for each operation
 pub fn parse(input: &str) -> IResult<&str, (Vec<Vec<u64>>, Vec<Operation>)> {
     let line = separated_list1(space1, nom::character::complete::u64);
     let lines = separated_list1(multispace1, line);
@@ -39,74 +44,84 @@ pub fn parse(input: &str) -> IResult<&str, (Vec<Vec<u64>>, Vec<Operation>)> {
         }),
     );
 
-    let (rest, (numbers, operations)) =
-        separated_pair(lines, multispace0, operations).parse(input.trim())?;
-    debug_assert!(rest.is_empty(), "Unparsed input remaining");
-
-    Ok((rest, (numbers, operations)))
+    verify(
+        separated_pair(lines, multispace0, operations),
+        |(numbers, operations): &(Vec<Vec<u64>>, Vec<Operation>)| {
+            numbers.iter().all(|row| row.len() == operations.len())
+        },
+    )
+    .parse(input.trim())
 }
 
-pub fn solve_a(input: &str) -> u64 {
-    let (_, (numbers, operations)) = parse(input).expect("Failed to parse input");
+const OVERFLOW: &str = "The answer does not fit into 64 bits";
 
-    // Transpose the numbers array, so we can access it by columns
-    let array = Array2::from_shape_vec(
-        (numbers.len(), numbers[0].len()),
-        numbers.iter().flatten().cloned().collect(),
-    )
-    .expect("Failed to create array");
+pub fn solve_a(input: &str) -> Result<u64, String> {
+    let (numbers, operations) = parse_all(parse, input)?;
+
+    // All rows have the same length, so we can access the numbers by columns
+    let grid = Grid::from_rows(numbers).expect("Failed to create grid");
 
     // Iterate over each column and corresponding operation
-    let colums = array.columns();
-    colums
-        .into_iter()
-        .zip(operations.into_iter())
-        .map(|(colum, operation)| match operation {
-            Operation::Add => colum.sum(),
-            Operation::Multiply => colum.product(),
+    (0..grid.width())
+        .zip(operations)
+        .try_fold(0u64, |total, (col, operation)| {
+            let mut colum = grid.column(col);
+            let value = match operation {
+                Operation::Add => colum.try_fold(0u64, |acc, &n| acc.checked_add(n)),
+                Operation::Multiply => colum.try_fold(1u64, |acc, &n| acc.checked_mul(n)),
+            };
+            value.and_then(|value| total.checked_add(value))
         })
-        .sum()
+        .ok_or(OVERFLOW.to_string())
 }
 
-pub fn solve_b(input: &str) -> u64 {
-    let data = input.trim().split(|b| b == '\n').collect::<Vec<_>>();
-    let (mut nums, mut current, mut total, mut op) = (Vec::with_capacity(4), 0, 0, 0);
-
-    for i in 0..data[0].len() {
+pub fn solve_b(input: &str) -> Result<u64, String> {
+    // Only the layout of the digits matters here, but the input still has to be valid
+    parse_all(parse, input)?;
+
+    // Pad the lines, so every column has a cell in each row
+    let lines = input
+        .trim()
+        .split('\n')
+        .map(|line| line.as_bytes().to_vec())
+        .collect();
+    let grid = Grid::from_ragged_rows(lines, b' ').expect("Input is empty");
+    let operators = grid.row(grid.height() - 1);
+    let (mut nums, mut current, mut total, mut op) = (Vec::<u8>::with_capacity(4), 0u64, 0u64, 0);
+
+    for (i, &new) in operators.iter().enumerate() {
         nums.clear();
         nums.extend(
-            data[..data.len() - 1]
-                .iter()
-                .map(|line| line.as_bytes()[i])
-                .filter(|&b| b != b' '),
+            grid.column(i)
+                .take(grid.height() - 1)
+                .filter(|b| b.is_ascii_digit()),
         );
         if nums.is_empty() {
-            total += current;
+            total = total.checked_add(current).ok_or(OVERFLOW)?;
+            current = 0;
             continue;
         }
 
         let value = nums
             .iter()
-            .rev()
-            .enumerate()
-            .map(|(i, &b)| (b - b'0') as usize * 10_usize.pow(i as u32))
-            .sum::<usize>();
-
-        let new = data.last().unwrap().as_bytes().get(i).unwrap_or(&b' ');
-        if *new != b' ' {
-            op = *new;
+            .try_fold(0u64, |acc, &b| {
+                acc.checked_mul(10)?.checked_add(u64::from(b - b'0'))
+            })
+            .ok_or(OVERFLOW)?;
+
+        if new == b'+' || new == b'*' {
+            op = new;
             current = (op == b'*') as _;
         }
 
-        if op == b'+' {
-            current += value;
+        current = if op == b'+' {
+            current.checked_add(value)
         } else {
-            current *= value;
+            current.checked_mul(value)
         }
+        .ok_or(OVERFLOW)?;
     }
-    total += current;
-
-    return total as u64;
+    total.checked_add(current).ok_or(OVERFLOW.to_string())
 }
 
 #[cfg(test)]
@@ -124,11 +139,11 @@ mod tests {
 
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 4277556);
+        assert_eq!(solve_a(EXAMPLE), Ok(4277556));
     }
 
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 3263827);
+        assert_eq!(solve_b(EXAMPLE), Ok(3263827));
     }
 }
@@ -4,7 +4,8 @@ crate::solution!(
     r"The key insight for Part 2 is that you need a seperate array to track how many new rays get added per splitter",
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = ".......S.......
@@ -24,81 +25,92 @@ static EXAMPLE: &str = ".......S.......
 .^.^.^.^.^...^.
 ...............";
 
-pub fn solve_a(input: &str) -> u64 {
-    let mut lines = input
-        .trim()
-        .split('\n')
-        .map(|line| line.as_bytes().to_vec())
-        .collect::<Vec<_>>();
+use nom::{IResult, Parser, combinator::verify};
 
-    for x in 0..lines[0].len() {
-        if lines[0][x] == b'S' {
-            lines[1][x] = b'|';
-        }
+use crate::grid::{Grid, grid};
+use crate::parsing::parse_all;
+
+/// Parses the manifold. It has to be rectangular with a single `S` in the first row.
+pub fn parse(input: &str) -> IResult<&str, Grid<char>> {
+    let manifold = grid(|c| ".S^".contains(c).then_some(c));
+    verify(manifold, |manifold: &Grid<char>| {
+        manifold.iter().filter(|&&c| c == 'S').count() == 1 && manifold.row(0).contains(&'S')
+    })
+    .parse(input.trim())
+}
+
+pub fn solve_a(input: &str) -> Result<u64, String> {
+    let mut grid = parse_all(parse, input)?;
+    if grid.height() < 2 {
+        return Ok(0);
+    }
+    let width = grid.width();
+
+    if let Some(x) = grid.row(0).iter().position(|&c| c == 'S') {
+        grid[(1, x)] = '|';
     }
 
     let mut splits = 0;
-    for y in (2..lines.len() - 1).step_by(2) {
-        for x in 0..lines[0].len() {
+    for y in (2..grid.height() - 1).step_by(2) {
+        for x in 0..width {
             // If not a spliiter
-            if lines[y][x] != b'^' {
+            if grid[(y, x)] != '^' {
                 // But there might be a continued ray
-                if lines[y - 1][x] == b'|' {
+                if grid[(y - 1, x)] == '|' {
                     // Continue pipe down
-                    lines[y][x] = b'|';
-                    lines[y + 1][x] = b'|';
+                    grid[(y, x)] = '|';
+                    grid[(y + 1, x)] = '|';
                 }
                 continue;
             };
             // Check above
-            if lines[y - 1][x] != b'|' {
+            if grid[(y - 1, x)] != '|' {
                 continue;
             }
-            // Add new pipe left and right
+            // Add new pipe left and right, rays leaving the manifold are lost
             splits += 1;
-            lines[y][x - 1] = b'|';
-            lines[y + 1][x - 1] = b'|';
-            lines[y][x + 1] = b'|';
-            lines[y + 1][x + 1] = b'|';
+            for x in [x.wrapping_sub(1), x + 1] {
+                if x < width {
+                    grid[(y, x)] = '|';
+                    grid[(y + 1, x)] = '|';
+                }
+            }
         }
     }
-    splits
+    Ok(splits)
 }
 
-pub fn solve_b(input: &str) -> u64 {
-    let lines = input
-        .trim()
-        .split('\n')
-        .map(|inp| inp.as_bytes())
-        .filter(|&line| !line.iter().all(|&b| b == b'.'))
-        .collect::<Vec<&[u8]>>();
+const OVERFLOW: &str = "The number of paths does not fit into 64 bits";
 
-    let mut paths = vec![0u64; lines[0].len()];
-    paths[lines[0].iter().position(|&b| b == b'S').unwrap()] = 1;
+pub fn solve_b(input: &str) -> Result<u64, String> {
+    let grid = parse_all(parse, input)?;
+    let lines = grid
+        .rows()
+        .filter(|&line| !line.iter().all(|&c| c == '.'))
+        .collect::<Vec<&[char]>>();
+
+    let width = grid.width();
+    let mut paths = vec![0u64; width];
+    paths[lines[0].iter().position(|&c| c == 'S').unwrap()] = 1;
 
     for row in lines[1..].iter() {
-        for i in 0..row.len() {
-            if row[i] == b'^' {
+        for i in 0..width {
+            if row[i] == '^' {
                 let count = paths[i];
                 paths[i] = 0;
-                paths[i - 1] += count;
-                paths[i + 1] += count;
+                if i > 0 {
+                    paths[i - 1] = paths[i - 1].checked_add(count).ok_or(OVERFLOW)?;
+                }
+                if i + 1 < width {
+                    paths[i + 1] = paths[i + 1].checked_add(count).ok_or(OVERFLOW)?;
+                }
             }
         }
     }
-    paths.into_iter().sum()
-}
-
-#[allow(dead_code)]
-fn print_grid(grid: &Vec<Vec<u8>>) {
-    for row in grid {
-        print_row(row);
-    }
-}
-
-#[allow(dead_code)]
-fn print_row(row: &[u8]) {
-    println!("{}", String::from_utf8_lossy(row));
+    paths
+        .into_iter()
+        .try_fold(0, u64::checked_add)
+        .ok_or(OVERFLOW.to_string())
 }
 
 #[cfg(test)]
@@ -107,11 +119,18 @@ mod tests {
 
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 21);
+        assert_eq!(solve_a(EXAMPLE), Ok(21));
     }
 
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 40);
+        assert_eq!(solve_b(EXAMPLE), Ok(40));
+    }
+
+    #[test]
+    fn test_overflow() {
+        // A pyramid of splitters 80 rows deep
+        let input = include_str!("../fuzz/regressions/day07/paths-overflow");
+        assert_eq!(solve_b(input), Err(OVERFLOW.to_string()));
     }
 }
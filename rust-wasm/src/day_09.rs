@@ -1,11 +1,14 @@
 crate::solution!(
     9,
     "Movie Theater",
-    r#"This is best solved with the <a href="https://en.wikipedia.org/wiki/Point_in_polygon">Point in Polygon algorithm</a> for the general case. However, that approach is slow, so we can optimize and specialize it by checking if a line intersects an area and considering it invalid. This only works for axis-aligned polygon edges, but that's all we need for the puzzle input.
+    r#"The red tiles form a polygon with only horizontal and vertical edges. There are only a few hundred distinct coordinates, so the polygon can be <a href="https://en.wikipedia.org/wiki/Coordinate_compression">compressed</a>: every coordinate of a corner gets its own row or column, and the tiles between two of them are merged into one. The compressed grid is small enough to fill in the green tiles with a scanline. A <a href="https://en.wikipedia.org/wiki/Summed-area_table">summed-area table</a> over it then tells if a rectangle is completely red or green in constant time, so every pair of red tiles can be checked.
+
+Inputs with diagonal edges don't fit on the compressed grid. They fall back to exact <a href="https://en.wikipedia.org/wiki/Point_in_polygon#Winding_number_algorithm">winding number</a> and segment intersection tests with integer cross products, which checks each rectangle in linear time.
 "#,
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "7,1
@@ -17,225 +20,122 @@ static EXAMPLE: &str = "7,1
 2,3
 7,3";
 
-use std::fmt;
-
-use nom::{
-    IResult, Parser,
-    character::complete::{self, char},
-    multi::separated_list1,
-};
+use nom::{IResult, Parser, combinator::verify};
 
 use rayon::prelude::*;
 
-pub fn parse(input: &str) -> IResult<&str, Vec<(u64, u64)>> {
-    let tile = ((complete::u64, char(','), complete::u64)).map(|(x, _, y): (u64, _, u64)| (x, y));
-    let (rest, tiles) = separated_list1(complete::line_ending, tile).parse(input.trim())?;
-    debug_assert!(rest.is_empty(), "Unparsed input remaining");
-    Ok((rest, tiles))
-}
-
-pub fn solve_a(input: &str) -> u64 {
-    let (_, tiles) = parse(input).expect("Failed to parse tiles");
-    // print_tiles(&tiles);
+use crate::geometry::SimplePolygon;
+use crate::parsing::{coords, lines, parse_all};
+use crate::point::Point;
+use crate::polygon::RectilinearPolygon;
 
-    // index x, index y, area
-    let mut max: Option<(usize, usize, u64)> = None;
-    for x_i in 0..tiles.len() {
-        for y_i in x_i + 1..tiles.len() {
-            let (x, y) = (tiles[x_i], tiles[y_i]);
-            let area = (x.0.abs_diff(y.0) + 1) * (x.1.abs_diff(y.1) + 1);
-            if let Some((_, _, max_area)) = max {
-                if area > max_area {
-                    max = Some((x_i, y_i, area));
-                }
-            } else {
-                max = Some((x_i, y_i, area));
-            }
-        }
-    }
-    assert!(max.is_some(), "No maximum area found");
-    let max = max.unwrap();
-    // let (max_x, max_y) = (tiles[max.0], tiles[max.1]);
-    max.2
+/// Coordinates are limited to 32 bits, so areas always fit into a u128
+pub fn parse(input: &str) -> IResult<&str, Vec<Point<2>>> {
+    let tile = coords::<2>.map(|coords| Point(coords.map(i64::from)));
+    // A rectangle needs two corners
+    verify(lines(tile), |tiles: &Vec<Point<2>>| tiles.len() >= 2).parse(input.trim())
 }
 
-#[derive(Debug, Clone, Copy)]
-struct AreaPair {
-    x_index: usize,
-    y_index: usize,
-    area: u64,
+/// Number of tiles in the rectangle with the opposite corners `a` and `b`
+fn area(a: &Point<2>, b: &Point<2>) -> u128 {
+    (u128::from(a[0].abs_diff(b[0])) + 1) * (u128::from(a[1].abs_diff(b[1])) + 1)
 }
 
-impl AreaPair {
-    fn print<T>(&self, tiles: &[(T, T)])
-    where
-        T: fmt::Display,
-    {
-        let (x, y) = (&tiles[self.x_index], &tiles[self.y_index]);
-        println!(
-            "({:3},{:3}) | ({:3},{:3}) | {:5}",
-            x.0, x.1, y.0, y.1, self.area
-        );
-    }
+/// Every rectangle with two red tiles as opposite corners
+fn rectangles(tiles: &[Point<2>]) -> impl ParallelIterator<Item = (&Point<2>, &Point<2>)> {
+    (0..tiles.len())
+        .into_par_iter()
+        .flat_map_iter(move |a| tiles[a + 1..].iter().map(move |b| (&tiles[a], b)))
 }
 
-#[allow(dead_code)]
-fn print_areas<T>(areas: &Vec<AreaPair>, tiles: &[(T, T)])
-where
-    T: fmt::Display,
-{
-    if areas.len() > 0 {
-        println!("--  X  -- | --  Y  -- |  Area");
-    }
-    for area in areas {
-        area.print(tiles);
-    }
+/// The largest rectangle with two red tiles as opposite corners
+fn largest_rectangle(tiles: &[Point<2>]) -> Option<(Point<2>, Point<2>)> {
+    rectangles(tiles)
+        .max_by_key(|(a, b)| area(a, b))
+        .map(|(a, b)| (*a, *b))
 }
 
-#[allow(dead_code)]
-fn print_tiles(tiles: &Vec<(u64, u64)>) {
-    let max_x = tiles.iter().map(|&(x, _)| x).max().unwrap();
-    let max_y = tiles.iter().map(|&(_, y)| y).max().unwrap();
-
-    for y in 0..=max_y {
-        for x in 0..=max_x {
-            if tiles.contains(&(x, y)) {
-                print!("#");
-            } else {
-                print!(".");
-            }
-        }
-        println!();
-    }
+/// The largest rectangle with two red tiles as opposite corners, that only covers red and green tiles
+fn largest_green_rectangle(tiles: &[Point<2>]) -> Result<Option<(Point<2>, Point<2>)>, String> {
+    let Ok(polygon) = RectilinearPolygon::new(tiles) else {
+        return Ok(SimplePolygon::new(tiles.to_vec())?.largest_rectangle());
+    };
+
+    // The compressed polygon checks a rectangle in constant time, so every one can be checked
+    Ok(rectangles(tiles)
+        .filter(|(a, b)| polygon.contains_rect(a, b))
+        .max_by_key(|(a, b)| area(a, b))
+        .map(|(a, b)| (*a, *b)))
 }
 
-#[allow(dead_code)]
-fn print_tiles_green<T>(tiles: &Vec<(T, T)>)
-where
-    T: Into<i128> + Copy,
-{
-    let tiles: Vec<(i128, i128)> = tiles.iter().map(|&(x, y)| (x.into(), y.into())).collect();
-    let max_x = tiles.iter().map(|&(x, _)| x).max().unwrap();
-    let max_y = tiles.iter().map(|&(_, y)| y).max().unwrap();
-
-    // Create lines from tiles
-    let mut lines: Vec<((i128, i128), (i128, i128))> = Vec::with_capacity(tiles.len());
-    for i in 0..tiles.len() {
-        let next = (i + 1) % tiles.len();
-        let start = (tiles[i].0 as i128, tiles[i].1 as i128);
-        let end = (tiles[next].0 as i128, tiles[next].1 as i128);
-        lines.push((start, end));
-    }
-
-    for y in 0..=max_y {
-        for x in 0..=max_x {
-            if tiles.contains(&(x, y)) {
-                print!("#");
-            } else if line_intersects_rect(&((x, y), (x, y)), &lines) {
-                print!("X");
-            } else {
-                print!(".");
-            }
-        }
-        println!();
-    }
+pub fn solve_a(input: &str) -> Result<u128, String> {
+    let tiles = parse_all(parse, input)?;
+    let (a, b) = largest_rectangle(&tiles).ok_or("No valid area found".to_string())?;
+    Ok(area(&a, &b))
 }
 
-pub fn solve_b(input: &str) -> u64 {
-    let (_, tiles) = parse(input).expect("Failed to parse tiles");
-    // print_tiles_green(&tiles);
-
-    // Calculate all areas
-    let mut areas: Vec<AreaPair> = Vec::with_capacity(tiles.len() * (tiles.len() - 1) / 2);
-    for x_i in 0..tiles.len() {
-        for y_i in x_i + 1..tiles.len() {
-            let (x, y) = (tiles[x_i], tiles[y_i]);
-            let area = (x.0.abs_diff(y.0) + 1) * (x.1.abs_diff(y.1) + 1);
-            areas.push(AreaPair {
-                x_index: x_i,
-                y_index: y_i,
-                area,
-            });
-        }
-    }
-    // Sort areas descending
-    areas.sort_unstable_by(|a, b| b.area.cmp(&a.area));
-
-    // Create lines from tiles
-    let mut lines: Vec<((i128, i128), (i128, i128))> = Vec::with_capacity(tiles.len());
-    for i in 0..tiles.len() {
-        let next = (i + 1) % tiles.len();
-        let start = (tiles[i].0 as i128, tiles[i].1 as i128);
-        let end = (tiles[next].0 as i128, tiles[next].1 as i128);
-        lines.push((start, end));
-    }
-
-    // Convert to i128
-    let tiles: Vec<(i128, i128)> = tiles.iter().map(|&(x, y)| (x as i128, y as i128)).collect();
-
-    // For each area, check if all sided are green
-    let max = areas
-        .into_par_iter()
-        .find_first(|area| {
-            let (x, y) = (tiles[area.x_index], tiles[area.y_index]);
-
-            let (min_x, max_x) = if x.0 < y.0 { (x.0, y.0) } else { (y.0, x.0) };
-            let (min_y, max_y) = if x.1 < y.1 { (x.1, y.1) } else { (y.1, x.1) };
-
-            !line_intersects_rect(&((min_x + 1, min_y + 1), (max_x - 1, max_y - 1)), &lines)
-        })
-        .unwrap_or_else(|| {
-            panic!("No valid area found");
-        });
-    return max.area;
+pub fn solve_b(input: &str) -> Result<u128, String> {
+    let tiles = parse_all(parse, input)?;
+    let (a, b) = largest_green_rectangle(&tiles)?.ok_or("No valid area found".to_string())?;
+    Ok(area(&a, &b))
 }
 
-fn line_intersects_rect(
-    rect: &((i128, i128), (i128, i128)),
-    lines: &[((i128, i128), (i128, i128))],
-) -> bool {
-    let ((rx1, ry1), (rx2, ry2)) = rect;
-
-    let rect_lines = vec![
-        ((*rx1, *ry1), (*rx2, *ry1)), // top
-        ((*rx2, *ry1), (*rx2, *ry2)), // right
-        ((*rx2, *ry2), (*rx1, *ry2)), // bottom
-        ((*rx1, *ry2), (*rx1, *ry1)), // left
-    ];
-
-    for line in lines {
-        for rect_line in &rect_lines {
-            if lines_intersect(line, rect_line) {
-                return true;
-            }
-        }
-    }
-
-    false
-}
-
-/// Check if two lines intersect, only for straight lines
-fn lines_intersect(
-    line1: &((i128, i128), (i128, i128)),
-    line2: &((i128, i128), (i128, i128)),
-) -> bool {
-    let ((x1, y1), (x2, y2)) = line1;
-    let ((x3, y3), (x4, y4)) = line2;
-
-    // Check if lines are vertical or horizontal
-    let line1_vertical = x1 == x2;
-    let line1_horizontal = y1 == y2;
-    let line2_vertical = x3 == x4;
-    let line2_horizontal = y3 == y4;
-
-    if line1_vertical && line2_horizontal {
-        // line1 is vertical, line2 is horizontal
-        return x1 >= x3.min(x4) && x1 <= x3.max(x4) && y3 >= y1.min(y2) && y3 <= y1.max(y2);
-    } else if line1_horizontal && line2_vertical {
-        // line1 is horizontal, line2 is vertical
-        return x3 >= x1.min(x2) && x3 <= x1.max(x2) && y1 >= y3.min(y4) && y1 <= y3.max(y4);
+/// Draws the red tiles, the green tiles between them and the rectangles of both parts as an SVG.
+/// One unit is one tile, the view box scales it to `SVG_SIZE` pixels.
+pub fn render_svg(input: &str) -> Result<String, String> {
+    const SVG_SIZE: i64 = 800;
+    let tiles = parse_all(parse, input)?;
+    let part_a = largest_rectangle(&tiles);
+    let part_b = largest_green_rectangle(&tiles)?;
+
+    let (min_x, max_x) = (tiles.iter().map(|t| t[0]).min(), tiles.iter().map(|t| t[0]).max());
+    let (min_y, max_y) = (tiles.iter().map(|t| t[1]).min(), tiles.iter().map(|t| t[1]).max());
+    let (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) = (min_x, max_x, min_y, max_y) else {
+        return Err("No red tiles".to_string());
+    };
+    // One tile of margin on each side
+    let (width, height) = (max_x - min_x + 3, max_y - min_y + 3);
+    // The longer side gets `SVG_SIZE` pixels
+    let scale = SVG_SIZE as f64 / width.max(height) as f64;
+    let (pixel_width, pixel_height) = (
+        (width as f64 * scale).ceil() as i64,
+        (height as f64 * scale).ceil() as i64,
+    );
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{pixel_width}" height="{pixel_height}" viewBox="{} {} {width} {height}">
+"#,
+        min_x - 1,
+        min_y - 1
+    );
+    // The polygon goes through the middle of the red tiles. The green stroke, half a tile on each
+    // side, covers the rest of the tiles on the edges.
+    let points: Vec<String> = tiles
+        .iter()
+        .map(|t| format!("{}.5,{}.5", t[0], t[1]))
+        .collect();
+    let points = points.join(" ");
+    svg += &format!(
+        r#"<polygon points="{points}" fill="green" stroke="green" stroke-width="1" stroke-linejoin="miter"/>
+<polygon points="{points}" fill="none" stroke="red" stroke-width="2" vector-effect="non-scaling-stroke"/>
+"#
+    );
+    for (rectangle, color, part) in [(part_a, "blue", 1), (part_b, "gold", 2)] {
+        let Some((a, b)) = rectangle else {
+            continue;
+        };
+        svg += &format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{color}" fill-opacity="0.3" stroke="{color}" stroke-width="2" vector-effect="non-scaling-stroke"><title>Part {part}: {a} to {b}, {} tiles</title></rect>
+"#,
+            a[0].min(b[0]),
+            a[1].min(b[1]),
+            a[0].abs_diff(b[0]) + 1,
+            a[1].abs_diff(b[1]) + 1,
+            area(&a, &b)
+        );
     }
-    false
+    svg += "</svg>\n";
+    Ok(svg)
 }
 
 #[cfg(test)]
@@ -248,26 +148,44 @@ mod tests {
         assert!(remaining.is_empty(), "Unparsed input remaining");
         assert_eq!(
             parsed,
-            vec![
-                (7, 1),
-                (11, 1),
-                (11, 7),
-                (9, 7),
-                (9, 5),
-                (2, 5),
-                (2, 3),
-                (7, 3),
-            ]
+            [[7, 1], [11, 1], [11, 7], [9, 7], [9, 5], [2, 5], [2, 3], [7, 3]].map(Point)
         );
     }
 
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 50);
+        assert_eq!(solve_a(EXAMPLE), Ok(50));
     }
 
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 24);
+        assert_eq!(solve_b(EXAMPLE), Ok(24));
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let svg = render_svg(EXAMPLE).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"viewBox="1 0 12 9""#));
+        assert!(svg.contains(r#"points="7.5,1.5 11.5,1.5 11.5,7.5"#));
+        assert!(svg.contains("<title>Part 1: 11,7 to 2,3, 50 tiles</title>"));
+        assert!(svg.contains("<title>Part 2: 9,5 to 2,3, 24 tiles</title>"));
+        assert!(render_svg("1,2").is_err());
+    }
+
+    #[test]
+    fn test_b_notch() {
+        // The empty notch between the two arms is surrounded by red and green tiles, but isn't green itself
+        let input = "0,0\n2,0\n2,5\n20,5\n20,0\n22,0\n22,7\n0,7";
+        assert_eq!(solve_b(input), Ok(21 * 3));
+    }
+
+    #[test]
+    fn test_b_diagonal() {
+        // Only the two straight edges fit inside the triangle
+        assert_eq!(solve_b("0,0\n5,0\n0,5"), Ok(6));
+        assert_eq!(solve_b("0,0\n4,0\n4,2\n8,6\n0,6"), Ok(5 * 7));
+        // Edges that cross each other don't form a polygon
+        assert!(solve_b("0,0\n4,4\n4,0\n0,4").is_err());
     }
 }
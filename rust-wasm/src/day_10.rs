@@ -1,7 +1,7 @@
 crate::solution!(
     10,
     "Factory",
-    r#"For Part 1, it's a simple <a href="https://en.wikipedia.org/wiki/Depth-first_search">Depth-first search</a>. For Part 2, I'm using the <a href="https://github.com/Z3Prover/z3">Z3 Theorem Prover</a> to set constraints and find the minimum. Sadly, this is not available for WebAssembly, so it can't run here on the website. So I also implemented another solution.<br><br>
+    r#"Pressing a button twice toggles the lights back, so in Part 1 every button is pressed at most once. That makes it linear algebra over <a href="https://en.wikipedia.org/wiki/GF(2)">GF(2)</a>, where adding is xor. <a href="https://en.wikipedia.org/wiki/Gaussian_elimination">Gaussian elimination</a> finds one set of buttons, and every other set differs from it by a combination of the null space. Only those $2^{free}$ combinations have to be checked for the fewest buttons. Part 2 is harder.<br><br>
 
 The problem can be rewritten as a set of linear equations that can be solved.  
 For the first machine in the example input, with the buttons $b_0,b_1,b_2,b_3,b_4,b_5$ and target joltages $3,5,4,7$:<br>
@@ -15,29 +15,32 @@ b_i &\geq 0
 \min(\sum_{i=0}^{5} b_i )
 $$<br>
 
-Sadly, this is not easily solvable with <a href="https://en.wikipedia.org/wiki/Gaussian_elimination">Gaussian elimination</a> because there remain free variables. It's actually an <a href="https://en.wikipedia.org/wiki/Integer_programming">Integer Programming</a> problem, which is <a href="https://en.wikipedia.org/wiki/NP-hardness">NP-hard</a>. But we can solve it with the <a href="https://en.wikipedia.org/wiki/Simplex_algorithm">Simplex algorithm</a> that is implemented in the <a href="https://crates.io/crates/good_lp">good_lp</a> crate.
+Sadly, this is not solvable with Gaussian elimination alone, because there remain free variables. It's actually an <a href="https://en.wikipedia.org/wiki/Integer_programming">Integer Programming</a> problem, which is <a href="https://en.wikipedia.org/wiki/NP-hardness">NP-hard</a>. The <a href="https://en.wikipedia.org/wiki/Simplex_algorithm">Simplex algorithm</a> only solves it for real numbers, and rounding that solution can be wrong. So after the elimination, only the few free variables are searched. Each one is limited by the smallest joltage it adds to, and every pivot variable has to stay a non negative integer. This gives the exact minimum, also here on the website.
 "#,
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
 [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
 
-use std::collections::{HashSet, VecDeque};
-
 use nom::{
     IResult, Parser,
-    branch::alt,
-    character::complete::{self, char, multispace1, space1},
-    multi::{many1, separated_list1},
+    character::complete::{self, char, space1},
+    combinator::verify,
+    multi::separated_list1,
     sequence::delimited,
 };
 
 use rayon::prelude::*;
 
+use crate::gf2::{BitMatrix, BitVector};
+use crate::ilp::IntegerProgram;
+use crate::parsing::{cell_row, parse_all, whitespace_separated};
+
 pub struct Machine {
     pub lights: Vec<bool>,
     pub buttons: Vec<Vec<usize>>,
@@ -45,20 +48,16 @@ pub struct Machine {
 }
 
 pub fn parse(input: &str) -> IResult<&str, Vec<Machine>> {
-    let lights = delimited(
-        char('['),
-        many1(alt((char('#').map(|_| true), char('.').map(|_| false)))),
-        char(']'),
-    );
+    let lights = delimited(char('['), cell_row('#', '.'), char(']'));
     let button = delimited(
         char('('),
-        separated_list1(char(','), complete::u64.map(|n| n as usize)),
+        separated_list1(char(','), complete::u32.map(|n| n as usize)),
         char(')'),
     );
     let buttons = separated_list1(space1, button);
     let joltage = delimited(
         char('{'),
-        separated_list1(char(','), complete::u64.map(|n| n as usize)),
+        separated_list1(char(','), complete::u32.map(|n| n as usize)),
         char('}'),
     );
     let machine =
@@ -69,151 +68,460 @@ pub fn parse(input: &str) -> IResult<&str, Vec<Machine>> {
                 joltage,
             }
         });
-    separated_list1(multispace1, machine).parse(input.trim())
+    // Buttons can only toggle existing lights, and every light needs a joltage
+    let machine = verify(machine, |m: &Machine| {
+        m.buttons.iter().flatten().all(|&light| light < m.lights.len())
+            && m.joltage.len() == m.lights.len()
+    });
+    whitespace_separated(machine).parse(input.trim())
 }
 
-pub fn solve_a(input: &str) -> u64 {
-    let (_, machines) = parse(input).expect("Failed to parse");
-    let total_steps: usize = machines
+pub fn solve_a(input: &str) -> Result<u64, String> {
+    let machines = parse_all(parse, input)?;
+    machines
         .into_par_iter()
-        .map(|machine| {
-            let start_state = vec![false; machine.lights.len()];
-            let end_state = machine.lights.clone();
-
-            let mut visited = HashSet::new();
-            let mut queue = VecDeque::new();
-            queue.push_back((start_state.to_vec(), 0));
-
-            let mut result = None;
-            while let Some((current_state, depth)) = queue.pop_front() {
-                if current_state == end_state {
-                    result = Some(depth);
-                    break;
-                }
+        .map(|machine| Ok(fewest_buttons(&machine)?.len() as u64))
+        .sum()
+}
 
-                // Generate next states
-                for button in &machine.buttons {
-                    let mut next_state = current_state.to_vec();
-                    for &index in button {
-                        next_state[index] = !next_state[index];
-                    }
+/// Indices of the fewest buttons that turn on the lights
+fn fewest_buttons(machine: &Machine) -> Result<Vec<usize>, String> {
+    let target: BitVector = machine.lights.iter().copied().collect();
+    let solutions = light_matrix(machine)
+        .solve(&target)
+        .ok_or("The lights of a machine can't be reached".to_string())?;
+    Ok(solutions.min_weight()?.ones().collect())
+}
 
-                    // If not visited, add to queue
-                    if visited.insert(next_state.clone()) {
-                        queue.push_back((next_state, depth + 1));
-                    }
-                }
-            }
-            result.expect("No solution found for machine")
-        })
-        .sum();
-    total_steps as u64
+/// One row per light and one column per button, set if the button toggles the light
+fn light_matrix(machine: &Machine) -> BitMatrix {
+    let mut matrix = BitMatrix::zeros(machine.lights.len(), machine.buttons.len());
+    for (b, button) in machine.buttons.iter().enumerate() {
+        for &light in button {
+            matrix.set(light, b, true);
+        }
+    }
+    matrix
 }
 
-#[cfg(all(feature = "good_lp", not(feature = "z3")))]
-pub fn solve_b(input: &str) -> u64 {
-    use good_lp::{
-        Expression, Solution, SolverModel, solvers::microlp::microlp, variable, variables,
-    };
+pub fn solve_b(input: &str) -> Result<u64, String> {
+    solve_b_with(input, default_solver())
+}
 
-    let (_, machines) = parse(input).expect("Failed to parse");
+pub fn solve_b_with(input: &str, solver: &dyn LinearSolver) -> Result<u64, String> {
+    let machines = parse_all(parse, input)?;
 
     machines
         .into_par_iter()
         .map(|machine| {
-            let mut problem = variables!();
+            let presses = solver
+                .presses(&machine)
+                .map_err(|e| format!("Failed to solve Machine: {e}"))?;
+            Ok(presses.iter().sum::<u64>())
+        })
+        .sum()
+}
 
-            // Button press variables
-            let button_vars: Vec<_> = (0..machine.buttons.len())
-                .map(|_| problem.add(variable().min(0)))
-                .collect();
+/// Part 2 with the solver called `name`, or with `all` of them to compare their answers
+pub fn solve_b_using(input: &str, name: &str) -> Result<String, String> {
+    if name == "all" {
+        return cross_check(input).map(|check| check.to_string());
+    }
+    solve_b_with(input, find_solver(name)?).map(|presses| presses.to_string())
+}
+
+fn find_solver(name: &str) -> Result<&'static dyn LinearSolver, String> {
+    solvers()
+        .into_iter()
+        .find(|solver| solver.name() == name)
+        .ok_or_else(|| {
+            let names: Vec<_> = solvers().iter().map(|solver| solver.name()).collect();
+            format!("Unknown solver '{name}', available are {} and all", names.join(", "))
+        })
+}
+
+/// Finds the fewest presses of each button, so the joltages are reached
+pub trait LinearSolver: Sync {
+    fn name(&self) -> &'static str;
+
+    /// Number of presses of each button
+    fn presses(&self, machine: &Machine) -> Result<Vec<u64>, String>;
+}
+
+/// Every solver that is compiled in
+pub fn solvers() -> Vec<&'static dyn LinearSolver> {
+    vec![
+        &Exact,
+        #[cfg(feature = "good_lp")]
+        &Microlp,
+        #[cfg(all(feature = "z3", not(target_arch = "wasm32")))]
+        &Z3,
+    ]
+}
+
+/// Z3 if it is compiled in, otherwise the exact solver, which also works in wasm
+pub fn default_solver() -> &'static dyn LinearSolver {
+    #[cfg(all(feature = "z3", not(target_arch = "wasm32")))]
+    let solver = &Z3;
+    #[cfg(not(all(feature = "z3", not(target_arch = "wasm32"))))]
+    let solver = &Exact;
+    solver
+}
+
+/// The integer programming solver from [`crate::ilp`]
+pub struct Exact;
 
-            // Joltage constraints
-            let joltage_constraints: Vec<_> = machine
-                .joltage
+impl LinearSolver for Exact {
+    fn name(&self) -> &'static str {
+        "exact"
+    }
+
+    fn presses(&self, machine: &Machine) -> Result<Vec<u64>, String> {
+        joltage_program(machine)?.solve()
+    }
+}
+
+/// One equation per joltage: the presses of the buttons connected to it add up to the joltage
+fn joltage_program(machine: &Machine) -> Result<IntegerProgram, String> {
+    let equations = (0..machine.joltage.len())
+        .map(|j| {
+            machine
+                .buttons
                 .iter()
-                .enumerate()
-                .map(|(j, &joltage)| {
-                    let mut sum = Expression::from(0);
-
-                    for b in 0..machine.buttons.len() {
-                        if machine.buttons[b].contains(&j) {
-                            sum = sum + &button_vars[b];
-                        }
+                .map(|button| button.contains(&j) as i64)
+                .collect()
+        })
+        .collect();
+    let joltage = machine.joltage.iter().map(|&j| j as i64).collect();
+    IntegerProgram::new(equations, joltage, vec![1; machine.buttons.len()])
+}
+
+/// Solves the integer program with the branch and bound of microlp
+#[cfg(feature = "good_lp")]
+pub struct Microlp;
+
+#[cfg(feature = "good_lp")]
+impl LinearSolver for Microlp {
+    fn name(&self) -> &'static str {
+        "microlp"
+    }
+
+    fn presses(&self, machine: &Machine) -> Result<Vec<u64>, String> {
+        use good_lp::{
+            Expression, Solution, SolverModel, solvers::microlp::microlp, variable, variables,
+        };
+
+        let mut problem = variables!();
+
+        // Button press variables
+        let button_vars: Vec<_> = (0..machine.buttons.len())
+            .map(|_| problem.add(variable().integer().min(0)))
+            .collect();
+
+        // Joltage constraints
+        let joltage_constraints: Vec<_> = machine
+            .joltage
+            .iter()
+            .enumerate()
+            .map(|(j, &joltage)| {
+                let mut sum = Expression::from(0);
+
+                for (button, &var) in machine.buttons.iter().zip(&button_vars) {
+                    if button.contains(&j) {
+                        sum += var;
                     }
-                    sum.eq(joltage as u32)
-                })
-                .collect();
+                }
+                sum.eq(joltage as u32)
+            })
+            .collect();
+
+        // Objective: Minimize total button presses
+        let objective = button_vars
+            .iter()
+            .fold(good_lp::Expression::from(0), |acc, b| acc + b);
+
+        // Solve
+        let solution = problem
+            .minimise(objective)
+            .using(microlp)
+            .with_all(joltage_constraints)
+            .solve()
+            .map_err(|e| e.to_string())?;
+
+        // The values are integers, only stored as floats
+        Ok(button_vars
+            .iter()
+            .map(|b| solution.value(*b).round() as u64)
+            .collect())
+    }
+}
+
+/// The Z3 optimizer, not available in wasm
+#[cfg(all(feature = "z3", not(target_arch = "wasm32")))]
+pub struct Z3;
 
-            // Objective: Minimize total button presses
-            let objective = button_vars
+#[cfg(all(feature = "z3", not(target_arch = "wasm32")))]
+impl LinearSolver for Z3 {
+    fn name(&self) -> &'static str {
+        "z3"
+    }
+
+    fn presses(&self, machine: &Machine) -> Result<Vec<u64>, String> {
+        use z3::{Optimize, SatResult, ast::Int};
+
+        let opt = Optimize::new();
+        let button_vars: Vec<Int> = (0..machine.buttons.len())
+            .map(|i| Int::new_const(format!("b{}", i)))
+            .collect();
+
+        // At least one
+        for b in &button_vars {
+            opt.assert(&b.ge(&Int::from_i64(0)));
+        }
+
+        // Joltage constraints
+        for (j, joltage) in machine.joltage.iter().enumerate() {
+            let mut sum = Int::from_i64(0);
+
+            for b in 0..machine.buttons.len() {
+                if machine.buttons[b].contains(&j) {
+                    sum = sum + &button_vars[b];
+                }
+            }
+            opt.assert(&sum.eq(Int::from_i64(*joltage as i64)));
+        }
+
+        // Minimize total button presses
+        let total_presses = button_vars.iter().fold(Int::from_i64(0), |acc, b| acc + b);
+        opt.minimize(&total_presses);
+
+        // Solve
+        let model = match opt.check(&[]) {
+            SatResult::Sat => opt.get_model().ok_or("Z3 found no model")?,
+            _ => return Err("No solution found for machine".to_string()),
+        };
+        button_vars
+            .iter()
+            .map(|b| {
+                model
+                    .eval(b, true)
+                    .and_then(|presses| presses.as_u64())
+                    .ok_or("Z3 returned an invalid number of presses".to_string())
+            })
+            .collect()
+    }
+}
+
+/// The answers of every solver, per machine
+pub struct CrossCheck {
+    pub solvers: Vec<&'static str>,
+    /// Total presses of each machine, in the order of `solvers`
+    pub machines: Vec<Vec<Result<u64, String>>>,
+}
+
+impl CrossCheck {
+    /// Index and answers of the machines where the solvers don't agree
+    pub fn disagreements(&self) -> impl Iterator<Item = (usize, &[Result<u64, String>])> {
+        self.machines
+            .iter()
+            .enumerate()
+            .filter(|(_, answers)| answers.iter().any(|answer| answer != &answers[0]))
+            .map(|(i, answers)| (i, answers.as_slice()))
+    }
+}
+
+impl std::fmt::Display for CrossCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let answer = |answer: &Result<u64, String>| match answer {
+            Ok(presses) => presses.to_string(),
+            Err(e) => format!("Error: {e}"),
+        };
+        for (s, name) in self.solvers.iter().enumerate() {
+            let total: Result<u64, String> = self.machines.iter().map(|m| m[s].clone()).sum();
+            writeln!(f, "{name}: {}", answer(&total))?;
+        }
+
+        let mut disagreements = 0;
+        for (machine, answers) in self.disagreements() {
+            disagreements += 1;
+            let answers: Vec<_> = self
+                .solvers
                 .iter()
-                .fold(good_lp::Expression::from(0), |acc, b| acc + b);
-
-            // Solve
-            let solution = problem
-                .minimise(objective)
-                .using(microlp)
-                .with_all(joltage_constraints)
-                .solve()
-                .expect("Failed to solve Machine");
-
-            // Sum button presses
-            button_vars
+                .zip(answers)
+                .map(|(name, a)| format!("{name} {}", answer(a)))
+                .collect();
+            writeln!(f, "Machine {}: {}", machine + 1, answers.join(", "))?;
+        }
+        match disagreements {
+            0 => write!(f, "All solvers agree"),
+            n => write!(f, "Different answers for {n} of {} machines", self.machines.len()),
+        }
+    }
+}
+
+/// Runs every solver on every machine
+pub fn cross_check(input: &str) -> Result<CrossCheck, String> {
+    let machines = parse_all(parse, input)?;
+    let solvers = solvers();
+    let machines = machines
+        .par_iter()
+        .map(|machine| {
+            solvers
                 .iter()
-                .map(|b| solution.value(*b) as u64)
-                .sum::<u64>()
+                .map(|solver| solver.presses(machine).map(|p| p.iter().sum()))
+                .collect()
         })
-        .sum()
+        .collect();
+    Ok(CrossCheck {
+        solvers: solvers.iter().map(|solver| solver.name()).collect(),
+        machines,
+    })
 }
 
-#[cfg(all(feature = "z3", not(target_arch = "wasm32")))]
-pub fn solve_b(input: &str) -> u64 {
-    use z3::{Optimize, SatResult, ast::Int};
+/// How one machine was solved, to find out which one is wrong when an answer is rejected
+pub struct Explanation {
+    pub lights: Vec<bool>,
+    pub buttons: Vec<Vec<usize>>,
+    pub joltage: Vec<usize>,
+    /// Indices of the buttons pressed in part 1
+    pub pressed: Result<Vec<usize>, String>,
+    /// Presses of each button in part 2
+    pub presses: Result<Vec<u64>, String>,
+    pub solver: &'static str,
+}
 
-    let (_, machines) = parse(input).expect("Failed to parse");
+impl Explanation {
+    pub fn new(machine: &Machine, solver: &dyn LinearSolver) -> Self {
+        Explanation {
+            lights: machine.lights.clone(),
+            buttons: machine.buttons.clone(),
+            joltage: machine.joltage.clone(),
+            pressed: fewest_buttons(machine),
+            presses: solver.presses(machine),
+            solver: solver.name(),
+        }
+    }
 
-    machines
-        .into_par_iter()
-        .map(|machine| {
-            let opt = Optimize::new();
-            let button_vars: Vec<Int> = (0..machine.buttons.len())
-                .map(|i| Int::new_const(format!("b{}", i)))
-                .collect();
+    /// Toggles the lights with the pressed buttons, without any of the linear algebra
+    pub fn lights_verified(&self) -> bool {
+        let Ok(pressed) = &self.pressed else {
+            return false;
+        };
+        let mut lights = vec![false; self.lights.len()];
+        for &light in pressed.iter().flat_map(|&b| &self.buttons[b]) {
+            lights[light] = !lights[light];
+        }
+        lights == self.lights
+    }
 
-            // At least one
-            for b in &button_vars {
-                opt.assert(&b.ge(&Int::from_i64(0)));
+    /// Adds up the joltage of every press
+    pub fn joltage_verified(&self) -> bool {
+        let Ok(presses) = &self.presses else {
+            return false;
+        };
+        let mut joltage = vec![0; self.joltage.len()];
+        for (button, &presses) in self.buttons.iter().zip(presses) {
+            for &j in button {
+                joltage[j] += presses as usize;
             }
+        }
+        presses.len() == self.buttons.len() && joltage == self.joltage
+    }
+}
 
-            // Joltage constraints
-            for (j, joltage) in machine.joltage.iter().enumerate() {
-                let mut sum = Int::from_i64(0);
-
-                for b in 0..machine.buttons.len() {
-                    if machine.buttons[b].contains(&j) {
-                        sum = sum + &button_vars[b];
-                    }
-                }
-                opt.assert(&sum.eq(Int::from_i64(*joltage as i64)));
+impl std::fmt::Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lights: String = self
+            .lights
+            .iter()
+            .map(|&on| if on { '#' } else { '.' })
+            .collect();
+        writeln!(f, "[{lights}]")?;
+
+        match &self.pressed {
+            Ok(pressed) => {
+                let buttons: Vec<_> = pressed
+                    .iter()
+                    .map(|&b| format!("({})", join(&self.buttons[b])))
+                    .collect();
+                writeln!(
+                    f,
+                    "  Part 1: {} -> {} presses, {}",
+                    buttons.join(" "),
+                    pressed.len(),
+                    verified(self.lights_verified())
+                )?;
             }
+            Err(e) => writeln!(f, "  Part 1: Error: {e}")?,
+        }
 
-            // Minimize total button presses
-            let total_presses = button_vars.iter().fold(Int::from_i64(0), |acc, b| acc + b);
-            opt.minimize(&total_presses);
-
-            // Solve
-            let model = match opt.check(&[]) {
-                SatResult::Sat => opt.get_model().unwrap(),
-                _ => panic!("No solution found for machine"),
-            };
-            // Sum button presses
-            button_vars
+        write!(f, "{}", self.part_2())
+    }
+}
+
+impl Explanation {
+    /// The line of part 2, on its own so several solvers can be shown for one machine
+    pub fn part_2(&self) -> String {
+        match &self.presses {
+            Ok(presses) => format!(
+                "  Part 2 ({}): [{}] -> {} presses, {} {{{}}}",
+                self.solver,
+                join(presses),
+                presses.iter().sum::<u64>(),
+                verified(self.joltage_verified()),
+                join(&self.joltage)
+            ),
+            Err(e) => format!("  Part 2 ({}): Error: {e}", self.solver),
+        }
+    }
+}
+
+/// Every machine with the buttons of part 1 and the presses of part 2, each checked again by
+/// simulating the machine
+pub fn explain(input: &str, solver: &dyn LinearSolver) -> Result<String, String> {
+    explain_with(input, &[solver])
+}
+
+/// Like [`explain`], with a line for part 2 of every solver
+pub fn explain_with(input: &str, solvers: &[&dyn LinearSolver]) -> Result<String, String> {
+    let machines = parse_all(parse, input)?;
+    let explanations: Vec<String> = machines
+        .par_iter()
+        .enumerate()
+        .map(|(i, machine)| {
+            let explanations: Vec<Explanation> = solvers
                 .iter()
-                .map(|b| model.eval(b, true).unwrap().as_u64().unwrap() as u64)
-                .sum::<u64>()
+                .map(|&solver| Explanation::new(machine, solver))
+                .collect();
+            let mut text = format!("Machine {} {}", i + 1, explanations[0]);
+            for explanation in &explanations[1..] {
+                text += "\n";
+                text += &explanation.part_2();
+            }
+            text
         })
-        .sum()
+        .collect();
+    Ok(explanations.join("\n"))
+}
+
+/// Explains every machine with the solver called `name`, or with every solver for `all`
+pub fn explain_using(input: &str, name: &str) -> Result<String, String> {
+    if name == "all" {
+        return explain_with(input, &solvers());
+    }
+    explain(input, find_solver(name)?)
+}
+
+fn verified(ok: bool) -> &'static str {
+    if ok { "verified" } else { "NOT VERIFIED" }
+}
+
+fn join<T: std::fmt::Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(T::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
 #[cfg(test)]
@@ -229,41 +537,105 @@ mod tests {
 
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 7);
+        assert_eq!(solve_a(EXAMPLE), Ok(7));
     }
 
     #[test]
     fn test_solve_a_parts() {
         let lines = EXAMPLE.trim().lines().collect::<Vec<_>>();
         assert_eq!(lines.len(), 3, "Expected 3 lines in example");
-        let solutions = vec![2, 3, 2];
+        let solutions = [2, 3, 2];
 
         for (line, sol) in lines.iter().zip(solutions.iter()) {
             let (remaining, parsed) = parse(line).expect("Failed to parse line");
             assert!(remaining.is_empty(), "Unparsed input remaining in line");
             assert_eq!(parsed.len(), 1, "Expected 1 machine per line");
 
-            assert_eq!(solve_a(line), *sol, "Unexpected solution for line");
+            assert_eq!(solve_a(line), Ok(*sol), "Unexpected solution for line");
         }
     }
 
+    #[test]
+    fn test_too_many_buttons() {
+        // 70 buttons for the same light leave 69 free ones, that is too many to try them all
+        let line = format!("[#] {} {{1}}", vec!["(0)"; 70].join(" "));
+        assert!(solve_a(&line).is_err());
+    }
+
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 33);
+        assert_eq!(solve_b(EXAMPLE), Ok(33));
     }
 
     #[test]
     fn test_solve_b_parts() {
         let lines = EXAMPLE.trim().lines().collect::<Vec<_>>();
         assert_eq!(lines.len(), 3, "Expected 3 lines in example");
-        let solutions = vec![10, 12, 11];
+        let solutions = [10, 12, 11];
 
         for (line, sol) in lines.iter().zip(solutions.iter()) {
             let (remaining, parsed) = parse(line).expect("Failed to parse line");
             assert!(remaining.is_empty(), "Unparsed input remaining in line");
             assert_eq!(parsed.len(), 1, "Expected 1 machine per line");
 
-            assert_eq!(solve_b(line), *sol, "Unexpected solution for line {}", line);
+            assert_eq!(
+                solve_b(line),
+                Ok(*sol),
+                "Unexpected solution for line {}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_solvers() {
+        for solver in solvers() {
+            assert_eq!(solve_b_with(EXAMPLE, solver), Ok(33), "{}", solver.name());
         }
+        assert_eq!(solve_b_using(EXAMPLE, "exact"), Ok("33".to_string()));
+        assert!(solve_b_using(EXAMPLE, "simplex").is_err());
+    }
+
+    #[test]
+    fn test_cross_check() {
+        let check = cross_check(EXAMPLE).unwrap();
+        assert_eq!(check.machines.len(), 3);
+        assert_eq!(check.disagreements().count(), 0);
+
+        // Generated machines have more buttons than the example
+        let generated = crate::generate::day_10(&mut crate::generate::Rng::new(3), 20);
+        assert_eq!(cross_check(&generated).unwrap().disagreements().count(), 0);
+
+        let check = CrossCheck {
+            solvers: vec!["exact", "microlp"],
+            machines: vec![vec![Ok(10), Ok(10)], vec![Ok(12), Ok(11)]],
+        };
+        assert_eq!(
+            check.to_string(),
+            "exact: 22\nmicrolp: 21\nMachine 2: exact 12, microlp 11\nDifferent answers for 1 of 2 machines"
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let line = EXAMPLE.lines().next().unwrap();
+        assert_eq!(
+            explain(line, &Exact),
+            Ok("Machine 1 [.##.]\n  Part 1: (1,3) (2,3) -> 2 presses, verified\n  Part 2 (exact): [1,5,0,1,3,0] -> 10 presses, verified {3,5,4,7}".to_string())
+        );
+
+        let all = explain_using(line, "all").unwrap();
+        assert_eq!(all.lines().count(), 2 + solvers().len());
+        assert!(all.contains("  Part 2 (exact): [1,5,0,1,3,0] -> 10 presses, verified"));
+        assert!(explain_using(line, "simplex").is_err());
+
+        let (_, machines) = parse(line).unwrap();
+        let mut explanation = Explanation::new(&machines[0], &Exact);
+        assert!(explanation.lights_verified());
+        assert!(explanation.joltage_verified());
+        explanation.pressed = Ok(vec![0]);
+        explanation.presses = Ok(vec![1, 3, 0, 3, 1, 1]);
+        assert!(!explanation.lights_verified());
+        assert!(!explanation.joltage_verified());
     }
 }
@@ -4,20 +4,19 @@ crate::solution!(
     r#"This is a classic <a href="https://en.wikipedia.org/wiki/Graph_theory">graph theory</a>
     problem. I model this as a <a href="https://en.wikipedia.org/wiki/Directed_acyclic_graph">DAG</a> and iterate over it in topological order while keeping track of connected nodes.
 
-    For Part 2, we can break down the problem into two distinct paths that can be combined:
-    $$\begin{align*}
-        \text{svr} \rightarrow \text{dac} \rightarrow \text{fft} \rightarrow \text{out} \\
-        \text{svr} \rightarrow \text{fft} \rightarrow \text{dac} \rightarrow \text{out}
-    \end{align*} $$
+    For Part 2, the paths have to visit $\text{dac}$ and $\text{fft}$. A path can only visit them in the same order as the topological order, so there is only one route left, for example:
+    $$\text{svr} \rightarrow \text{fft} \rightarrow \text{dac} \rightarrow \text{out}$$
+    The number of paths is the product of the paths of each segment.
 
     <div id="day11_example">
     <div class="label"><img src="day11_a.png" alt="Day 11 Part 1" id="day11_a"><span class="alt">Part 1</span></div>
     <div class="label"><img src="day11_b.png" alt="Day 11 Part 2" id="day11_b"><span class="alt">Part 2</span></div>
     </div>
-    Technically the second path is not needed and could be removed. Because the input data doesn't have a connection $\text{dac} \rightarrow \text{fft}$.<br><a href="day11.png">You can see the full graph here</a>."#,
+    <a href="day11.png">You can see the full graph here</a>."#,
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "aaa: you hhh
@@ -45,21 +44,21 @@ fff: ggg hhh
 ggg: out
 hhh: out";
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 use nom::{
     IResult, Parser,
     bytes::complete::take_while_m_n,
-    character::complete::{char, multispace1, space0, space1},
+    character::complete::{char, space0, space1},
+    combinator::verify,
     multi::separated_list1,
 };
 
-use petgraph::{
-    dot::{Config, Dot},
-    graph::DiGraph,
-    visit::Topo,
-};
-use rayon::prelude::*;
+use petgraph::graph::{DiGraph, NodeIndex};
+
+use crate::graph_export::{Format, Graph, Link, Node};
+use crate::parsing::{parse_all, whitespace_separated};
+use crate::path_count::PathCounter;
 
 pub fn parse(input: &str) -> IResult<&str, Vec<(&str, Vec<&str>)>> {
     let device = || take_while_m_n(3, 3, |c: char| c.is_ascii_alphabetic());
@@ -69,137 +68,121 @@ pub fn parse(input: &str) -> IResult<&str, Vec<(&str, Vec<&str>)>> {
         separated_list1(space1, device()),
     )
         .map(|(from, _, tos)| (from, tos));
-    let (rest, connections) = separated_list1(multispace1, connection).parse(input.trim())?;
-    debug_assert!(rest.is_empty(), "Unparsed input remaining");
-    debug_assert!(connections.len() > 1,);
-    Ok((rest, connections))
+    verify(whitespace_separated(connection), |connections: &Vec<_>| {
+        connections.len() > 1
+    })
+    .parse(input.trim())
 }
 
-fn connections_to_graph(connections: &Vec<(&str, Vec<&str>)>) -> DiGraph<String, ()> {
+/// The graph of all devices, with the index of each device by name
+fn connections_to_graph<'a>(
+    connections: &[(&'a str, Vec<&'a str>)],
+) -> (DiGraph<String, ()>, HashMap<&'a str, NodeIndex>) {
     let mut graph = DiGraph::<_, ()>::with_capacity(
         connections.len(),
         connections[0].1.len() * connections.len(),
     );
 
-    let mut unique_nodes: HashSet<&str> = HashSet::new();
-    for connection in connections {
-        unique_nodes.insert(connection.0);
-        for &to in &connection.1 {
-            unique_nodes.insert(to);
+    let mut nodes = HashMap::new();
+    for &(from, ref tos) in connections {
+        for &name in std::iter::once(&from).chain(tos) {
+            nodes
+                .entry(name)
+                .or_insert_with(|| graph.add_node(name.to_owned()));
         }
     }
 
-    // Build graph + node map in one iterator pass
-    let nodes: HashMap<&str, _> = unique_nodes
-        .iter()
-        .copied()
-        .map(|s| (s, graph.add_node(s.to_owned())))
-        .collect();
-
-    // Add all edges in iterator style
-    graph.extend_with_edges(
-        connections
-            .iter()
-            .flat_map(|connection| connection.1.iter().map(move |&to| (connection.0, to)))
-            .map(|(from, to)| {
-                (
-                    *nodes.get(from).unwrap(), // Safe: all nodes pre-added
-                    *nodes.get(to).unwrap(),
-                    (),
-                )
-            }),
-    );
+    for (from, tos) in connections {
+        for to in tos {
+            graph.add_edge(nodes[from], nodes[to], ());
+        }
+    }
 
-    graph
+    (graph, nodes)
 }
 
-pub fn solve_a(input: &str) -> u64 {
-    let (_, connections) = parse(input).expect("Failed to parse");
-    let graph = connections_to_graph(&connections);
-    connections_between(&graph, "you", "out")
+fn node(nodes: &HashMap<&str, NodeIndex>, name: &str) -> Result<NodeIndex, String> {
+    nodes
+        .get(name)
+        .copied()
+        .ok_or(format!("Unknown device '{name}'"))
 }
 
-#[allow(dead_code)]
-fn save_dot(graph: &DiGraph<String, ()>, path: &str) {
-    use std::fs::File;
-    use std::io::Write;
-
-    let fancy_dot = Dot::with_attr_getters(
-        &graph,
-        &[Config::EdgeNoLabel, Config::NodeNoLabel],
-        &|_graph_reference, _edge_reference| String::new(),
-        // Node attribute getter; We don't change any node attributes
-        &|_, (_, label)| {
-            let deco = match label.as_str() {
-                "you" | "svr" => {
-                    String::from(r#", shape = "box", style = "filled", fillcolor = "green""#)
-                }
-                "out" => String::from(r#", shape = "box", style = "filled", fillcolor = "red""#),
-                "dac" | "fft" => String::from(
-                    r#", shape = "diamond", style = "filled", fillcolor = "lightblue""#,
-                ),
-                _ => String::new(),
-            };
-            format!(r#"label = "{}"{}"#, label, deco)
-        },
-    );
-    let mut file = File::create(path).expect("Unable to create file");
-    write!(file, "{:?}", fancy_dot).expect("Unable to write data");
+pub fn solve_a(input: &str) -> Result<u128, String> {
+    let connections = parse_all(parse, input)?;
+    let (graph, nodes) = connections_to_graph(&connections);
+    let (you, out) = (node(&nodes, "you")?, node(&nodes, "out")?);
+
+    let counter = PathCounter::new(&graph)?;
+    counter.check_reachable(you, &[out])?;
+    counter.count(you, out)
 }
 
-pub fn solve_b(input: &str) -> u64 {
+pub fn solve_b(input: &str) -> Result<u128, String> {
     // Hack, so that example B can be tested with solve_a
     let inp = if input.trim() == EXAMPLE.trim() {
         EXAMPLE_B
     } else {
         input.trim()
     };
-    let (_, connections) = parse(inp).expect("Failed to parse");
-    let graph = connections_to_graph(&connections);
-
-    let routes = [
-        [("svr", "dac"), ("dac", "fft"), ("fft", "out")],
-        [("svr", "fft"), ("fft", "dac"), ("dac", "out")],
-    ];
-
-    routes
-        .into_par_iter()
-        .map(|route| {
-            route
-                .into_par_iter()
-                .map(|(from, to)| connections_between(&graph, from, to))
-                .product::<u64>()
-        })
-        .sum::<u64>()
+    let connections = parse_all(parse, inp)?;
+    let (graph, nodes) = connections_to_graph(&connections);
+
+    let (svr, out) = (node(&nodes, "svr")?, node(&nodes, "out")?);
+    let waypoints = [node(&nodes, "dac")?, node(&nodes, "fft")?];
+
+    let counter = PathCounter::new(&graph)?;
+    counter.check_reachable(svr, &[waypoints[0], waypoints[1], out])?;
+    counter.count_through(svr, out, &waypoints)
 }
 
-fn connections_between(graph: &DiGraph<String, ()>, from: &str, to: &str) -> u64 {
-    let mut connection_map = vec![0u64; graph.node_count()];
-
-    let from_index = graph
-        .node_indices()
-        .find(|&i| graph[i] == from)
-        .expect(&format!("No '{}' node found", from));
-
-    let to_index = graph
-        .node_indices()
-        .find(|&i| graph[i] == to)
-        .expect(&format!("No '{}' node found", to));
-
-    // Set 'from' node to 1
-    connection_map[from_index.index()] = 1;
-
-    // Traverse in graph
-    let mut topo = Topo::new(&graph);
-    while let Some(node_index) = topo.next(&graph) {
-        let paths_to_node: u64 = graph
-            .neighbors_directed(node_index, petgraph::Incoming)
-            .map(|neighbor| connection_map[neighbor.index()])
-            .sum();
-        connection_map[node_index.index()] += paths_to_node;
+/// Colours of the start devices, the waypoints and `out`, like on the website
+pub const DEFAULT_HIGHLIGHT: &str = "you=green,svr=green,dac=lightblue,fft=lightblue,out=red";
+
+/// The graph of all devices in `format`, with the number of paths from `from` to each device.
+/// The paths are left out if `from` is empty or the graph has a cycle. `highlight` is a comma
+/// separated list of `device=colour`, the colour defaults to lightblue. Devices that are not in
+/// the graph are skipped, so the same list works for both parts.
+pub fn export_graph(input: &str, format: &str, from: &str, highlight: &str) -> Result<String, String> {
+    let format: Format = format.parse()?;
+    let connections = parse_all(parse, input)?;
+    let (graph, nodes) = connections_to_graph(&connections);
+
+    let mut colours = HashMap::new();
+    for entry in highlight.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (name, colour) = entry.split_once('=').unwrap_or((entry, "lightblue"));
+        colours.insert(name, colour);
     }
 
-    connection_map[to_index.index()]
+    let paths = if from.is_empty() {
+        None
+    } else {
+        let from = node(&nodes, from)?;
+        PathCounter::new(&graph)
+            .and_then(|counter| counter.count_all(from))
+            .ok()
+    };
+
+    let export = Graph {
+        directed: true,
+        nodes: graph
+            .node_indices()
+            .map(|i| Node {
+                id: graph[i].clone(),
+                paths: paths.as_ref().map(|paths| paths[i.index()].to_string()),
+                highlight: colours.get(graph[i].as_str()).map(|c| c.to_string()),
+            })
+            .collect(),
+        links: graph
+            .edge_indices()
+            .filter_map(|e| graph.edge_endpoints(e))
+            .map(|(a, b)| Link {
+                source: graph[a].clone(),
+                target: graph[b].clone(),
+            })
+            .collect(),
+    };
+    Ok(export.export(format))
 }
 
 #[cfg(test)]
@@ -217,23 +200,41 @@ mod tests {
 
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 5);
+        assert_eq!(solve_a(EXAMPLE), Ok(5));
     }
 
     #[test]
     fn test_solve_b() {
-        assert_eq!(solve_b(EXAMPLE), 2);
+        assert_eq!(solve_b(EXAMPLE), Ok(2));
+    }
+
+    #[test]
+    fn test_errors() {
+        let cycle = "you: aaa\naaa: bbb\nbbb: aaa out";
+        assert!(solve_a(cycle).unwrap_err().starts_with("The graph has a cycle: "));
+        assert_eq!(
+            solve_a("you: aaa\naaa: bbb"),
+            Err("Unknown device 'out'".to_string())
+        );
+        assert_eq!(
+            solve_a("you: aaa\nbbb: out"),
+            Err("'out' can't be reached from 'you'".to_string())
+        );
     }
 
-    #[ignore]
     #[test]
-    fn test_print_graphs() {
-        let (_, connections) = parse(EXAMPLE).expect("Failed to parse");
-        let graph = connections_to_graph(&connections);
-        save_dot(&graph, "day11_a.dot");
-
-        let (_, connections_b) = parse(EXAMPLE_B).expect("Failed to parse");
-        let graph_b = connections_to_graph(&connections_b);
-        save_dot(&graph_b, "day11_b.dot");
+    fn test_export_graph() {
+        let dot = export_graph(EXAMPLE, "dot", "you", DEFAULT_HIGHLIGHT).unwrap();
+        assert!(dot.contains(
+            r#""out" [label = "out\n5", shape = "box", style = "filled", fillcolor = "red"]"#
+        ));
+        assert!(dot.contains(r#""aaa" [label = "aaa\n0"]"#));
+        assert_eq!(dot.matches(" -> ").count(), 17);
+
+        let json = export_graph(EXAMPLE, "json", "", "hhh=orange").unwrap();
+        assert!(json.contains(r#"{"id":"hhh","highlight":"orange"}"#));
+        assert!(export_graph(EXAMPLE, "graphml", "you", "").is_ok());
+        assert!(export_graph(EXAMPLE, "dot", "zzz", "").is_err());
+        assert!(export_graph(EXAMPLE, "dot", "you", "zzz=red").is_ok());
     }
 }
@@ -17,7 +17,7 @@ $$
 \end{array}
 $$
 
-Unfortunately the size of the resulting Matrix grows way too quickly and this problem is unsolvable in a reasonable amount of time.
+The size of the Matrix grows way too quickly for the real input though.
 For a 50x50 grid, with 6 presents, each having 8 variants with $(50-2)^2$ grid positions the number of possibilities is immense. We also need to account for the required amount of each present, with that being 50 on average.
 
 $$
@@ -26,10 +26,11 @@ $$
 \Rightarrow 1,5*10^{10} \text{ entries}
 $$
 
-Instead we just look at the input data and apply some trivial heuristics to guess if a packing could be valid. With that we get the right answer."#,
+Luckily the real input doesn't need it. If the presents have more cells than the region, they can't fit. If every present gets its own $3 \times 3$ square, they always fit. Every region of the input is one of these two. Only the regions in between, like the ones of the example, are packed with Dancing Links. The cells are secondary columns that can stay empty, and each present is one column that has to be covered as often as the present is needed. Copies of the same present are only placed in increasing order, so they are not tried in every order. A mirrored or turned packing is a packing too, so one present that is needed only once is kept to one side of the region. Symmetric presents have fewer than 8 different rotations and reflections, only those are placed."#,
     &EXAMPLE,
     solve_a,
-    solve_b
+    solve_b,
+    parse = parse
 );
 
 static EXAMPLE: &str = "0:
@@ -66,18 +67,20 @@ static EXAMPLE: &str = "0:
 12x5: 1 0 1 0 2 2
 12x5: 1 0 1 0 3 2";
 
-use std::collections::HashSet;
-
 use nom::{
     IResult, Parser,
-    branch::alt,
     character::complete::{self, char, multispace1, space1},
-    multi::{many1, separated_list1},
+    combinator::{consumed, verify},
+    multi::separated_list1,
 };
 
+use crate::dancing_links::DancingLinks;
+use crate::parsing::{parse_all, whitespace_separated};
+use crate::polyomino::{Board, Polyomino, polyomino};
+
 pub struct Present {
     pub index: u64,
-    pub shape: Vec<Vec<bool>>,
+    pub shape: Polyomino,
 }
 
 #[derive(Debug)]
@@ -90,97 +93,156 @@ pub struct Region {
 
 pub fn parse(input: &str) -> IResult<&str, (Vec<Present>, Vec<Region>)> {
     let index = (complete::u64, char(':'));
-    let shape = separated_list1(
-        multispace1,
-        many1(alt((char('#').map(|_| true), char('.').map(|_| false)))),
-    );
+    let shape = polyomino('#', '.');
     let present = (index, multispace1, shape).map(|((idx, _), _, sh)| Present {
         index: idx,
         shape: sh,
     });
-    let (rest, presents) = separated_list1(multispace1, present).parse(input.trim())?;
+    let (rest, presents) = whitespace_separated(consumed(present)).parse(input.trim())?;
 
-    let size = (complete::u64, char('x'), complete::u64).map(|(w, _, h)| (w, h));
-    let indices = separated_list1(space1, complete::u64);
+    // Sizes and counts are limited to 32 bits, so the areas can't overflow
+    let size = (complete::u32, char('x'), complete::u32).map(|(w, _, h)| (w.into(), h.into()));
+    let indices = separated_list1(space1, complete::u32.map(u64::from));
     let region = (size, char(':'), space1, indices).map(|((w, h), _, _, inds)| Region {
         width: w,
         height: h,
         shapes: inds,
     });
-    let (remaining, regions) = separated_list1(multispace1, region).parse(rest.trim())?;
-
-    debug_assert!(remaining.is_empty(), "Unparsed input remaining");
-    debug_assert!(
-        presents
-            .iter()
-            .map(|p| p.index)
-            .collect::<HashSet<u64>>()
-            .len()
-            == presents.len(),
-        "Duplicate present indices found"
-    );
-    debug_assert!(
-        regions.iter().all(|r| r.shapes.len() == presents.len()),
-        "Region does not reference all presents"
-    );
+    // The regions count the presents by position, so the presents have to be numbered in order
+    if let Some(((text, _), _)) = presents
+        .iter()
+        .zip(0..)
+        .find(|((_, present), position)| present.index != *position)
+    {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            text,
+            nom::error::ErrorKind::Verify,
+        )));
+    }
+    let presents: Vec<Present> = presents.into_iter().map(|(_, present)| present).collect();
+    // Every region has to list a count for each present
+    let count = presents.len();
+    let (remaining, regions) = verify(whitespace_separated(region), |regions: &Vec<Region>| {
+        regions.iter().all(|r| r.shapes.len() == count)
+    })
+    .parse(rest.trim())?;
 
     Ok((remaining, (presents, regions)))
 }
 
-pub fn solve_a(input: &str) -> u64 {
-    // This is bad. But its sadly required for this puzzle.
-    // The example is way harder than the acutal input. The acutal input is trivial to solve with
-    // heuristics, while the example needs actual packing logic. Actually packing the shapes would
-    // take hours or runtime and is not computable.
-    if input.trim() == EXAMPLE.trim() {
-        return 2;
-    }
-
-    let (_, (presents, regions)) = parse(input).expect("Failed to parse");
+pub fn solve_a(input: &str) -> Result<u64, String> {
+    let (presents, regions) = parse_all(parse, input)?;
     let mut total = 0;
+    for (i, region) in regions.iter().enumerate() {
+        let fits = match fits_trivially(&presents, region) {
+            Some(fits) => fits,
+            None => packs(&presents, region).map_err(|e| format!("Region {}: {e}", i + 1))?,
+        };
+        total += u64::from(fits);
+    }
+    Ok(total)
+}
 
-    for region in &regions {
-        let region_area = region.width * region.height;
-        let mut total_required_area = 0;
+/// Decides the easy regions without packing anything. There is not enough space if the cells of
+/// the presents add up to more than the area. And there is enough space if every present gets its
+/// own square, as big as the largest present. The real input only has regions like that.
+pub fn fits_trivially(presents: &[Present], region: &Region) -> Option<bool> {
+    let used = presents.iter().zip(&region.shapes).filter(|(_, count)| **count > 0);
+    let cells: u64 = used
+        .clone()
+        .map(|(present, &count)| present.shape.cell_count() as u64 * count)
+        .sum();
+    if cells > region.width * region.height {
+        return Some(false);
+    }
 
-        let mut total_badly_packed = 0;
+    let side = used
+        .map(|(present, _)| present.shape.width().max(present.shape.height()) as u64)
+        .max()
+        .unwrap_or(1);
+    let squares = (region.width / side) * (region.height / side);
+    if region.shapes.iter().sum::<u64>() <= squares {
+        return Some(true);
+    }
+    None
+}
 
-        for (present, &count) in presents.iter().zip(region.shapes.iter()) {
-            if count == 0 {
-                continue;
+/// Regions with more cells are not packed exactly
+const MAX_PACKING_AREA: u64 = 2_500;
+/// Placements that are tried before giving up on a region
+const MAX_PACKING_STEPS: u64 = 10_000_000;
+
+/// Packs the presents with Dancing Links. Every present is a primary column, that has to be
+/// covered as often as the present is needed. Every cell of the region is a secondary column,
+/// because cells can stay empty.
+fn packs(presents: &[Present], region: &Region) -> Result<bool, String> {
+    if region.width * region.height > MAX_PACKING_AREA {
+        return Err(format!(
+            "The region is too large to pack exactly, it has more than {MAX_PACKING_AREA} cells"
+        ));
+    }
+    // Boards are at most 64 cells wide. Turning the region doesn't change if it can be packed,
+    // and with at most 2500 cells one of the sides is short enough.
+    let (width, height) = if region.width > 64 {
+        (region.height as usize, region.width as usize)
+    } else {
+        (region.width as usize, region.height as usize)
+    };
+    let board = Board::new(height, width).ok_or("The region is too wide to pack")?;
+    let counts: Vec<usize> = region.shapes.iter().map(|&count| count as usize).collect();
+    let mut links = DancingLinks::new(&counts, width * height);
+    // Mirroring or turning a packed region packs it as well. So a present that is needed only
+    // once is only placed where its placement is the smallest of its mirrored and turned ones.
+    let pinned = counts.iter().position(|&count| count == 1);
+
+    for (p, present) in presents.iter().enumerate().filter(|&(p, _)| counts[p] > 0) {
+        let mut placed = false;
+        for orientation in &present.shape.orientations() {
+            for (top, left) in board.placements(orientation) {
+                placed = true;
+                let cells: Vec<(usize, usize)> = orientation
+                    .cells()
+                    .iter()
+                    .map(|&(r, c)| (top + r, left + c))
+                    .collect();
+                if pinned == Some(p) && !is_canonical(&cells, width, height) {
+                    continue;
+                }
+                let row: Vec<usize> = std::iter::once(p)
+                    .chain(cells.iter().map(|&(r, c)| counts.len() + r * width + c))
+                    .collect();
+                links.add_row(&row);
             }
-
-            let shape_area = present
-                .shape
-                .iter()
-                .map(|row| row.iter().filter(|&&cell| cell).count() as u64)
-                .sum::<u64>();
-            let total_shape_area = shape_area * count;
-
-            // We assume worst case packing is 3x3 blocks
-            total_badly_packed += 9 * count;
-
-            total_required_area += total_shape_area;
         }
-
-        if total_required_area >= region_area {
-            continue;
+        // A present that fits nowhere on the empty board can't be packed at all
+        if !placed {
+            return Ok(false);
         }
+    }
 
-        // Here i definitly know it works
-        if total_badly_packed < region_area {
-            total += 1;
-            continue;
-        }
+    let mut links = links.with_limit(MAX_PACKING_STEPS);
+    Ok(links.solve()?.is_some())
+}
 
-        // Kinda random logic to decide if it can be packed
-        // assume maybe a 5 area waste
-        if total_required_area + 5 <= region_area {
-            total += 1;
-            continue;
-        }
-    }
-    total
+/// Is the placement, with its cells sorted, not larger than its images under the symmetries of
+/// the region? Every region can be mirrored both ways, square ones can also be turned.
+fn is_canonical(cells: &[(usize, usize)], width: usize, height: usize) -> bool {
+    let (w, h) = (width - 1, height - 1);
+    let image = |symmetry: usize, (r, c): (usize, usize)| match symmetry {
+        0 => (r, w - c),
+        1 => (h - r, c),
+        2 => (h - r, w - c),
+        3 => (c, r),
+        4 => (c, h - r),
+        5 => (w - c, r),
+        _ => (w - c, h - r),
+    };
+    let symmetries = if width == height { 7 } else { 3 };
+    (0..symmetries).all(|symmetry| {
+        let mut image: Vec<_> = cells.iter().map(|&cell| image(symmetry, cell)).collect();
+        image.sort_unstable();
+        cells <= image.as_slice()
+    })
 }
 
 pub fn solve_b(_input: &str) -> String {
@@ -196,14 +258,79 @@ mod tests {
         let (remaining, (present, regions)) = parse(EXAMPLE).expect("Failed to parse");
         assert!(remaining.is_empty(), "Unparsed input remaining");
         assert_eq!(present.len(), 6);
+        assert_eq!(present[0].shape.bounding_box(), (3, 3));
+        assert_eq!(present[0].shape.cell_count(), 7);
         assert_eq!(regions.len(), 3);
         assert_eq!(regions[0].width, 4);
         assert_eq!(regions[0].height, 4);
         assert_eq!(regions[0].shapes.len(), 6);
     }
 
+    #[test]
+    fn test_present_order() {
+        // The counts of the regions are by position, so the indices have to match them
+        let swapped = "1:\n##\n\n0:\n#.\n##\n\n2x2: 1 1";
+        let error = solve_a(swapped).unwrap_err();
+        assert!(error.contains("line 1, column 1"), "{error}");
+        let repeated = "0:\n##\n\n0:\n#.\n##\n\n2x2: 1 1";
+        let error = solve_a(repeated).unwrap_err();
+        assert!(error.contains("line 4, column 1"), "{error}");
+        assert_eq!(solve_a("0:\n##\n\n1:\n#.\n##\n\n2x2: 0 1"), Ok(1));
+    }
+
     #[test]
     fn test_solve_a() {
-        assert_eq!(solve_a(EXAMPLE), 2);
+        assert_eq!(solve_a(EXAMPLE), Ok(2));
+    }
+
+    #[test]
+    fn test_symmetry() {
+        // A domino in the corner of a 3x2 region is canonical, the ones in the other corners not
+        assert!(is_canonical(&[(0, 0), (0, 1)], 3, 2));
+        assert!(!is_canonical(&[(0, 1), (0, 2)], 3, 2));
+        assert!(!is_canonical(&[(1, 0), (1, 1)], 3, 2));
+        // Square regions can be turned, so a standing domino is turned into a lying one
+        assert!(!is_canonical(&[(0, 0), (1, 0)], 3, 3));
+        assert!(is_canonical(&[(0, 0), (0, 1)], 3, 3));
+    }
+
+    #[test]
+    fn test_regions() {
+        let (_, (presents, regions)) = parse(EXAMPLE).expect("Failed to parse");
+        // None of the example regions is easy, they all need packing
+        for region in &regions {
+            assert_eq!(fits_trivially(&presents, region), None);
+        }
+        // A long region is turned to fit on a board, a present that is too big fails early
+        let long = Region {
+            width: 100,
+            height: 3,
+            shapes: vec![0, 0, 0, 0, 3, 0],
+        };
+        assert_eq!(packs(&presents, &long), Ok(true));
+        let thin = Region {
+            width: 100,
+            height: 2,
+            shapes: vec![1, 0, 0, 0, 0, 0],
+        };
+        assert_eq!(packs(&presents, &thin), Ok(false));
+
+        // Proving that the third one can't be packed is slow in debug builds, `test_solve_a`
+        // already does it
+        let packed: Vec<_> = regions[..2].iter().map(|r| packs(&presents, r)).collect();
+        assert_eq!(packed, vec![Ok(true), Ok(true)]);
+
+        let easy = Region {
+            width: 6,
+            height: 3,
+            shapes: vec![1, 1, 0, 0, 0, 0],
+        };
+        assert_eq!(fits_trivially(&presents, &easy), Some(true));
+        let full = Region {
+            width: 3,
+            height: 3,
+            shapes: vec![0, 0, 0, 0, 2, 0],
+        };
+        assert_eq!(fits_trivially(&presents, &full), Some(false));
     }
 }
@@ -0,0 +1,56 @@
+//! Entry points for the fuzz targets in `fuzz/`. Run them with `cargo +nightly fuzz run day08`.
+//!
+//! The inputs come from users pasting into the website, so nothing may panic, whatever the input
+//! is. Invalid inputs are reported by [`parse_all`] as errors from the parts.
+
+use crate::{Day, parsing::parse_all};
+
+fn check(day: &Day, input: &str) {
+    for part in [day.part1, day.part2].into_iter().flatten() {
+        part(input);
+    }
+}
+
+macro_rules! targets {
+    ( $( $day:ident ),* $(,)? ) => {
+        $(
+            pub fn $day(input: &str) {
+                let _ = parse_all(crate::$day::parse, input);
+                check(&crate::$day::SOLUTION, input);
+            }
+        )*
+
+        /// Name of each fuzz target with its entry point
+        pub static TARGETS: &[(&str, fn(&str))] = &[
+            $(
+                (stringify!($day), $day),
+            )*
+        ];
+    };
+}
+
+targets!(
+    day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09, day_10, day_11, day_12
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replays the crashes the fuzzer found, they are stored in `fuzz/regressions/<target>/`
+    #[test]
+    fn test_regressions() {
+        let regressions = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/regressions");
+        for (name, target) in TARGETS {
+            let Ok(files) = std::fs::read_dir(regressions.join(name.replace('_', ""))) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let input = std::fs::read(file.path()).expect("Failed to read regression");
+                if let Ok(input) = std::str::from_utf8(&input) {
+                    target(input);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,663 @@
+//! Seeded random input generators for every day, to test how the solvers scale and to find edge cases.
+//! The generated inputs follow the same format and constraints as the real puzzle inputs,
+//! so every solver can run on them.
+//!
+//! `size` is the number of items the input contains (rotations, ranges, cells, ...). At
+//! [`Generator::base_size`] the input is about as large as the real puzzle input.
+
+use std::collections::HashSet;
+
+/// Small and fast PRNG (SplitMix64), the same seed always generates the same input on every platform
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Random number in `low..high`
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        debug_assert!(low < high, "Empty range {low}..{high}");
+        low + self.next_u64() % (high - low)
+    }
+
+    /// Random index in `0..len`
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0, len as u64) as usize
+    }
+
+    /// True with the given probability
+    pub fn chance(&mut self, probability: f64) -> bool {
+        let uniform = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        uniform < probability
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+pub struct Generator {
+    pub day: u32,
+    /// Size that is comparable to the real puzzle input
+    pub base_size: usize,
+    pub generate: fn(&mut Rng, usize) -> String,
+}
+
+pub static GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        base_size: 4_500,
+        generate: day_01,
+    },
+    Generator {
+        day: 2,
+        base_size: 35,
+        generate: day_02,
+    },
+    Generator {
+        day: 3,
+        base_size: 200,
+        generate: day_03,
+    },
+    Generator {
+        day: 4,
+        base_size: 140 * 140,
+        generate: day_04,
+    },
+    Generator {
+        day: 5,
+        base_size: 190,
+        generate: day_05,
+    },
+    Generator {
+        day: 6,
+        base_size: 1_000,
+        generate: day_06,
+    },
+    Generator {
+        day: 7,
+        base_size: 142 * 141,
+        generate: day_07,
+    },
+    Generator {
+        day: 8,
+        base_size: 1_000,
+        generate: day_08,
+    },
+    Generator {
+        day: 9,
+        base_size: 500,
+        generate: day_09,
+    },
+    Generator {
+        day: 10,
+        base_size: 170,
+        generate: day_10,
+    },
+    Generator {
+        day: 11,
+        base_size: 600,
+        generate: day_11,
+    },
+    Generator {
+        day: 12,
+        base_size: 1_000,
+        generate: day_12,
+    },
+];
+
+/// Generates an input for `day` that is `scale` times as large as the real input
+pub fn generate(day: u32, seed: u64, scale: usize) -> Option<String> {
+    let generator = GENERATORS.iter().find(|g| g.day == day)?;
+    Some((generator.generate)(
+        &mut Rng::new(seed),
+        generator.base_size * scale,
+    ))
+}
+
+/// Rotations of the dial: `L68`
+pub fn day_01(rng: &mut Rng, rotations: usize) -> String {
+    (0..rotations)
+        .map(|_| {
+            let direction = if rng.chance(0.5) { 'L' } else { 'R' };
+            format!("{direction}{}", rng.range(1, 1_000))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Non overlapping ID ranges with up to 10 digits: `11-22,95-115`
+pub fn day_02(rng: &mut Rng, ranges: usize) -> String {
+    let mut starts: Vec<u64> = (0..ranges)
+        .map(|_| {
+            let digits = rng.range(1, 11) as u32;
+            rng.range(10u64.pow(digits - 1), 10u64.pow(digits))
+        })
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    starts.sort_unstable();
+
+    let mut pairs = Vec::with_capacity(starts.len());
+    for (i, &start) in starts.iter().enumerate() {
+        let digits = start.ilog10() + 1;
+        let width = rng.range(0, 10u64.pow(digits.div_ceil(2)) * 10);
+        let end = match starts.get(i + 1) {
+            Some(&next) => (start + width).min(next - 1),
+            None => start + width,
+        };
+        pairs.push(format!("{start}-{end}"));
+    }
+    rng.shuffle(&mut pairs);
+    pairs.join(",")
+}
+
+/// Battery banks with 100 digits each: `987654321111111`
+pub fn day_03(rng: &mut Rng, banks: usize) -> String {
+    (0..banks)
+        .map(|_| {
+            (0..100)
+                .map(|_| char::from(b'0' + rng.range(1, 10) as u8))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Square grid of paper rolls: `..@@.@@@@.`
+pub fn day_04(rng: &mut Rng, cells: usize) -> String {
+    let side = square_side(cells);
+    (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| if rng.chance(0.65) { '@' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Overlapping fresh ID ranges, followed by 5 IDs per range
+pub fn day_05(rng: &mut Rng, ranges: usize) -> String {
+    const MAX_ID: u64 = 500_000_000_000_000;
+
+    let ranges: Vec<(u64, u64)> = (0..ranges)
+        .map(|_| {
+            let start = rng.range(1, MAX_ID);
+            (start, start + rng.range(0, 20_000_000_000_000))
+        })
+        .collect();
+
+    let ids = (0..ranges.len() * 5).map(|_| {
+        // Half the IDs are fresh
+        if rng.chance(0.5) {
+            let (start, end) = ranges[rng.index(ranges.len())];
+            rng.range(start, end + 1)
+        } else {
+            rng.range(1, MAX_ID)
+        }
+    });
+
+    let ranges = ranges.iter().map(|(start, end)| format!("{start}-{end}"));
+    let ids = ids.map(|id| id.to_string());
+    format!(
+        "{}\n\n{}",
+        ranges.collect::<Vec<_>>().join("\n"),
+        ids.collect::<Vec<_>>().join("\n")
+    )
+}
+
+/// Math problems written in columns, 4 rows of numbers with up to 3 digits and a row of operators
+pub fn day_06(rng: &mut Rng, problems: usize) -> String {
+    const ROWS: usize = 4;
+    let mut lines = vec![String::new(); ROWS + 1];
+
+    for problem in 0..problems {
+        let width = rng.range(1, 4) as usize;
+        // One number needs the full width, otherwise a column would be empty and look like a separator.
+        // The first line can't start with a space, because the input gets trimmed.
+        let full_row = if problem == 0 { 0 } else { rng.index(ROWS) };
+        let right_aligned = problem != 0 && rng.chance(0.5);
+
+        for (row, line) in lines.iter_mut().take(ROWS).enumerate() {
+            if problem != 0 {
+                line.push(' ');
+            }
+            let digits = if row == full_row {
+                width
+            } else {
+                rng.range(1, width as u64 + 1) as usize
+            };
+            let number = (0..digits)
+                .map(|_| char::from(b'0' + rng.range(1, 10) as u8))
+                .collect::<String>();
+            if right_aligned {
+                line.push_str(&format!("{number:>width$}"));
+            } else {
+                line.push_str(&format!("{number:<width$}"));
+            }
+        }
+
+        let operator = if rng.chance(0.5) { '*' } else { '+' };
+        let line = &mut lines[ROWS];
+        if problem != 0 {
+            line.push(' ');
+        }
+        line.push_str(&format!("{operator:<width$}"));
+    }
+    lines.join("\n")
+}
+
+/// Tachyon manifold with a start `S` and splitters `^` in a cone below it.
+///
+/// The height stays at the real input's 142 rows, otherwise the number of timelines in part 2
+/// overflows. So larger sizes only make the grid wider.
+pub fn day_07(rng: &mut Rng, cells: usize) -> String {
+    const HEIGHT: usize = 142;
+    let width = (cells / HEIGHT).max(3) | 1;
+    let start = width / 2;
+
+    let mut grid = vec![vec![b'.'; width]; HEIGHT];
+    grid[0][start] = b'S';
+    for (k, row) in grid.iter_mut().skip(2).step_by(2).enumerate() {
+        // Like in the real input, splitters are never next to each other or on the border
+        for (x, cell) in row.iter_mut().enumerate().take(width - 1).skip(1) {
+            let reachable = x.abs_diff(start) <= k && (x + start + k).is_multiple_of(2);
+            if reachable && (k == 0 || rng.chance(0.7)) {
+                *cell = b'^';
+            }
+        }
+    }
+    grid.iter()
+        .map(|row| String::from_utf8_lossy(row).to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Unique junction boxes in 3D space: `162,817,812`
+pub fn day_08(rng: &mut Rng, boxes: usize) -> String {
+    let mut seen = HashSet::with_capacity(boxes);
+    let mut lines = Vec::with_capacity(boxes);
+    while lines.len() < boxes {
+        let position = (
+            rng.range(0, 100_000),
+            rng.range(0, 100_000),
+            rng.range(0, 100_000),
+        );
+        if seen.insert(position) {
+            lines.push(format!("{},{},{}", position.0, position.1, position.2));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Red tiles that form a simple rectilinear polygon: `7,1`
+///
+/// The polygon is x-monotone: a row of columns that all overlap in a horizontal band,
+/// each with a random top and bottom. Walking along the tops and back along the bottoms gives
+/// the corners. Equal neighbouring heights are merged, so no three corners are collinear.
+pub fn day_09(rng: &mut Rng, corners: usize) -> String {
+    const MIDDLE: u64 = 50_000;
+    let columns = (corners / 4).max(2);
+
+    let mut x = rng.range(1, 1_000);
+    let mut xs = vec![x];
+    let mut tops = Vec::with_capacity(columns);
+    let mut bottoms = Vec::with_capacity(columns);
+    for _ in 0..columns {
+        x += rng.range(2, 400);
+        xs.push(x);
+        tops.push(rng.range(MIDDLE + 1, 2 * MIDDLE));
+        bottoms.push(rng.range(1, MIDDLE));
+    }
+
+    // Walk along the top from left to right
+    let mut polygon = vec![(xs[0], bottoms[0]), (xs[0], tops[0])];
+    for i in 1..columns {
+        if tops[i] != tops[i - 1] {
+            polygon.push((xs[i], tops[i - 1]));
+            polygon.push((xs[i], tops[i]));
+        }
+    }
+    polygon.push((xs[columns], tops[columns - 1]));
+    polygon.push((xs[columns], bottoms[columns - 1]));
+    // And back along the bottom
+    for i in (1..columns).rev() {
+        if bottoms[i] != bottoms[i - 1] {
+            polygon.push((xs[i], bottoms[i]));
+            polygon.push((xs[i], bottoms[i - 1]));
+        }
+    }
+
+    polygon
+        .iter()
+        .map(|(x, y)| format!("{x},{y}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Machines that can always be solved: `[.##.] (3) (1,3) (2) {3,5,4,7}`
+///
+/// The lights and joltages are calculated from random button presses, so a solution exists.
+pub fn day_10(rng: &mut Rng, machines: usize) -> String {
+    (0..machines)
+        .map(|_| {
+            let lights = rng.range(3, 11) as usize;
+            let buttons: Vec<Vec<usize>> = (0..rng.range(lights as u64 - 1, lights as u64 + 4))
+                .map(|_| {
+                    let mut button: Vec<usize> = (0..lights).filter(|_| rng.chance(0.4)).collect();
+                    if button.is_empty() {
+                        button.push(rng.index(lights));
+                    }
+                    button
+                })
+                .collect();
+
+            let mut target = vec![false; lights];
+            let mut joltage = vec![0u64; lights];
+            for button in &buttons {
+                let toggled = rng.chance(0.5);
+                let presses = rng.range(0, 30);
+                for &light in button {
+                    target[light] ^= toggled;
+                    joltage[light] += presses;
+                }
+            }
+
+            let target = target
+                .iter()
+                .map(|&on| if on { '#' } else { '.' })
+                .collect::<String>();
+            let buttons = buttons
+                .iter()
+                .map(|button| {
+                    let lights = button.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+                    format!("({})", lights.join(","))
+                })
+                .collect::<Vec<_>>();
+            let joltage = joltage.iter().map(|j| j.to_string()).collect::<Vec<_>>();
+            format!("[{target}] {} {{{}}}", buttons.join(" "), joltage.join(","))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Layered DAG of devices with 3 letter names: `aaa: you hhh`
+///
+/// Every device connects to 1 to 3 random devices in the next layers, the last layer connects to `out`.
+/// `svr` is in the first layer, `you` and `fft` early on and `dac` later, like in the real input.
+/// A chain of devices through every layer makes sure that part 2 has at least one path.
+/// The number of layers is fixed, so the path counts don't overflow for large sizes.
+pub fn day_11(rng: &mut Rng, devices: usize) -> String {
+    const LAYERS: usize = 16;
+    const RESERVED: [&str; 5] = ["svr", "you", "fft", "dac", "out"];
+    // There are only 26^3 possible names
+    let devices = devices.clamp(LAYERS * 2, 17_000);
+
+    let mut names: HashSet<String> = RESERVED.iter().map(|s| s.to_string()).collect();
+    let mut layers: Vec<Vec<String>> = vec![Vec::new(); LAYERS];
+    for i in 0..devices - RESERVED.len() + 1 {
+        let name = loop {
+            let name = (0..3)
+                .map(|_| char::from(b'a' + rng.range(0, 26) as u8))
+                .collect::<String>();
+            if names.insert(name.clone()) {
+                break name;
+            }
+        };
+        layers[i % LAYERS].push(name);
+    }
+    layers[0].push("svr".to_string());
+    layers[2].push("you".to_string());
+    layers[LAYERS / 3].push("fft".to_string());
+    layers[2 * LAYERS / 3].push("dac".to_string());
+
+    // One device of every layer leads to the next one, so there is always a path
+    // svr -> fft -> dac -> out for part 2
+    let chain: Vec<&str> = layers
+        .iter()
+        .enumerate()
+        .map(|(layer, nodes)| match layer {
+            0 => "svr",
+            _ if layer == LAYERS / 3 => "fft",
+            _ if layer == 2 * LAYERS / 3 => "dac",
+            _ => nodes[rng.index(nodes.len())].as_str(),
+        })
+        .collect();
+
+    let mut lines = Vec::with_capacity(devices);
+    for (layer, nodes) in layers.iter().enumerate() {
+        for node in nodes {
+            let targets: Vec<&str> = if layer == LAYERS - 1 {
+                vec!["out"]
+            } else {
+                let mut targets = Vec::new();
+                if node == chain[layer] {
+                    targets.push(chain[layer + 1]);
+                }
+                for _ in 0..rng.range(1, 4) {
+                    // Mostly connect to the next layer, sometimes skip one
+                    let next = (layer + 1 + usize::from(rng.chance(0.2))).min(LAYERS - 1);
+                    let target = layers[next][rng.index(layers[next].len())].as_str();
+                    if !targets.contains(&target) {
+                        targets.push(target);
+                    }
+                }
+                targets
+            };
+            lines.push(format!("{node}: {}", targets.join(" ")));
+        }
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+/// Six 3x3 presents and regions to fill: `12x5: 1 0 1 0 2 2`
+///
+/// Like the real input, most regions either have plenty of space or clearly not enough.
+pub fn day_12(rng: &mut Rng, regions: usize) -> String {
+    let presents: Vec<[[bool; 3]; 3]> = (0..6).map(|_| random_present(rng)).collect();
+    let areas: Vec<u64> = presents
+        .iter()
+        .map(|p| p.iter().flatten().filter(|&&c| c).count() as u64)
+        .collect();
+
+    let mut output = String::new();
+    for (i, present) in presents.iter().enumerate() {
+        output.push_str(&format!("{i}:\n"));
+        for row in present {
+            let row = row.iter().map(|&c| if c { '#' } else { '.' });
+            output.push_str(&row.collect::<String>());
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+
+    let mut lines = Vec::with_capacity(regions);
+    for _ in 0..regions {
+        let (width, height) = (rng.range(35, 51), rng.range(35, 51));
+        let area = width * height;
+        let fits = rng.chance(0.5);
+
+        // Regions that fit have a 3x3 square for every present
+        let squares = (width / 3) * (height / 3) * 9;
+        let mut counts = [0u64; 6];
+        let mut used = 0;
+        loop {
+            let present = rng.index(6);
+            let needed = if fits { 9 } else { areas[present] };
+            if fits && used + needed > squares {
+                break;
+            }
+            counts[present] += 1;
+            used += needed;
+            if !fits && used > area {
+                break;
+            }
+        }
+
+        let counts = counts.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        lines.push(format!("{width}x{height}: {}", counts.join(" ")));
+    }
+    output.push_str(&lines.join("\n"));
+    output
+}
+
+/// Connected shape with 5 to 7 cells that still spans the full 3x3 box
+fn random_present(rng: &mut Rng) -> [[bool; 3]; 3] {
+    let mut shape = [[true; 3]; 3];
+    let target = rng.range(5, 8) as usize;
+    let mut filled = 9;
+    while filled > target {
+        let (y, x) = (rng.index(3), rng.index(3));
+        if !shape[y][x] {
+            continue;
+        }
+        shape[y][x] = false;
+        if spans_box(&shape) && is_connected(&shape, filled - 1) {
+            filled -= 1;
+        } else {
+            shape[y][x] = true;
+        }
+    }
+    shape
+}
+
+fn spans_box(shape: &[[bool; 3]; 3]) -> bool {
+    (0..3).all(|i| (0..3).any(|j| shape[i][j]) && (0..3).any(|j| shape[j][i]))
+}
+
+fn is_connected(shape: &[[bool; 3]; 3], filled: usize) -> bool {
+    let Some(start) = (0..9).find(|&i| shape[i / 3][i % 3]) else {
+        return false;
+    };
+    let mut seen = [false; 9];
+    let mut stack = vec![start];
+    seen[start] = true;
+    let mut count = 0;
+    while let Some(cell) = stack.pop() {
+        count += 1;
+        let (y, x) = (cell / 3, cell % 3);
+        let neighbours = [
+            (y > 0).then(|| cell - 3),
+            (y < 2).then_some(cell + 3),
+            (x > 0).then(|| cell - 1),
+            (x < 2).then_some(cell + 1),
+        ];
+        for next in neighbours.into_iter().flatten() {
+            if shape[next / 3][next % 3] && !seen[next] {
+                seen[next] = true;
+                stack.push(next);
+            }
+        }
+    }
+    count == filled
+}
+
+fn square_side(cells: usize) -> usize {
+    (cells as f64).sqrt().round().max(1.0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DAYS;
+
+    #[test]
+    fn test_deterministic() {
+        for generator in GENERATORS {
+            let a = (generator.generate)(&mut Rng::new(7), 50);
+            let b = (generator.generate)(&mut Rng::new(7), 50);
+            let c = (generator.generate)(&mut Rng::new(8), 50);
+            assert_eq!(a, b, "Day {} is not deterministic", generator.day);
+            assert_ne!(a, c, "Day {} ignores the seed", generator.day);
+        }
+    }
+
+    #[test]
+    fn test_all_days_solvable() {
+        // Day 8 needs enough boxes to still have 3 circuits after 1000 connections
+        let sizes = [(8, 1_000), (9, 40), (12, 20)];
+        for generator in GENERATORS {
+            let size = sizes
+                .iter()
+                .find(|(day, _)| *day == generator.day)
+                .map_or(30, |(_, size)| *size);
+            let input = (generator.generate)(&mut Rng::new(42), size);
+            let day = DAYS.iter().find(|d| d.day == generator.day).unwrap();
+            if let Some(parse) = day.parse {
+                parse(&input);
+            }
+            for part in [day.part1, day.part2].into_iter().flatten() {
+                part(&input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_06_columns() {
+        let input = day_06(&mut Rng::new(1), 50);
+        let lines: Vec<&str> = input.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[..4].iter().all(|l| l.len() == lines[0].len()));
+        assert!(!lines[0].starts_with(' '));
+    }
+
+    #[test]
+    fn test_day_09_rectilinear() {
+        let (_, tiles) = crate::day_09::parse(&day_09(&mut Rng::new(3), 200)).unwrap();
+        for i in 0..tiles.len() {
+            let (a, b) = (tiles[i], tiles[(i + 1) % tiles.len()]);
+            assert!(a != b && (a[0] == b[0] || a[1] == b[1]), "{a:?} -> {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_day_11_has_paths() {
+        for seed in 0..20 {
+            let input = day_11(&mut Rng::new(seed), 200);
+            let paths = crate::day_11::solve_b(&input);
+            assert!(
+                paths.as_ref().is_ok_and(|&p| p > 0),
+                "Seed {seed}: {paths:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_day_12_decidable() {
+        // Every region either clearly fits or clearly doesn't, like in the real input
+        for seed in 0..5 {
+            let input = day_12(&mut Rng::new(seed), 50);
+            let (_, (presents, regions)) = crate::day_12::parse(&input).unwrap();
+            for region in &regions {
+                assert!(
+                    crate::day_12::fits_trivially(&presents, region).is_some(),
+                    "Seed {seed}: {region:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_present() {
+        let mut rng = Rng::new(5);
+        for _ in 0..100 {
+            let present = random_present(&mut rng);
+            let filled = present.iter().flatten().filter(|&&c| c).count();
+            assert!((5..=7).contains(&filled));
+            assert!(spans_box(&present) && is_connected(&present, filled));
+        }
+    }
+}
@@ -0,0 +1,475 @@
+//! Exact geometry on integer points: orientation, segment intersection, point in polygon, and the
+//! largest rectangle inside a simple polygon.
+//!
+//! All tests use integer cross products, so there are no rounding errors. Coordinates have to stay
+//! within ±2^61, then the products fit into an i128.
+
+use std::cmp::Ordering;
+
+use rayon::prelude::*;
+
+use crate::point::Point;
+use crate::polygon::RectilinearPolygon;
+
+/// Twice the signed area of the triangle, positive if `a`, `b`, `c` turn counter clockwise
+fn cross(a: &Point<2>, b: &Point<2>, c: &Point<2>) -> i128 {
+    let (abx, aby) = (i128::from(b[0] - a[0]), i128::from(b[1] - a[1]));
+    let (acx, acy) = (i128::from(c[0] - a[0]), i128::from(c[1] - a[1]));
+    abx * acy - aby * acx
+}
+
+/// `Greater` if `a`, `b`, `c` turn counter clockwise, `Less` if clockwise, `Equal` if collinear.
+/// With y pointing down, like in the puzzles, counter clockwise looks clockwise on screen.
+pub fn orientation(a: &Point<2>, b: &Point<2>, c: &Point<2>) -> Ordering {
+    cross(a, b, c).cmp(&0)
+}
+
+/// Is `p` on the closed segment from `a` to `b`
+pub fn on_segment(p: &Point<2>, a: &Point<2>, b: &Point<2>) -> bool {
+    orientation(a, b, p).is_eq()
+        && (a[0].min(b[0])..=a[0].max(b[0])).contains(&p[0])
+        && (a[1].min(b[1])..=a[1].max(b[1])).contains(&p[1])
+}
+
+/// Do the closed segments `a`-`b` and `c`-`d` share a point, including touching ends and collinear overlaps
+pub fn segments_intersect(a: &Point<2>, b: &Point<2>, c: &Point<2>, d: &Point<2>) -> bool {
+    let (abc, abd) = (orientation(a, b, c), orientation(a, b, d));
+    let (cda, cdb) = (orientation(c, d, a), orientation(c, d, b));
+    if abc != abd && cda != cdb && [abc, abd, cda, cdb].iter().all(|o| o.is_ne()) {
+        return true;
+    }
+    on_segment(c, a, b) || on_segment(d, a, b) || on_segment(a, c, d) || on_segment(b, c, d)
+}
+
+/// Do the segments cross at a single point that is inside both of them, not at an end
+pub fn segments_cross(a: &Point<2>, b: &Point<2>, c: &Point<2>, d: &Point<2>) -> bool {
+    let (abc, abd) = (orientation(a, b, c), orientation(a, b, d));
+    let (cda, cdb) = (orientation(c, d, a), orientation(c, d, b));
+    abc.is_ne() && abd.is_ne() && abc != abd && cda.is_ne() && cdb.is_ne() && cda != cdb
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// The edges of a polygon, the last vertex connects back to the first one
+fn edges(polygon: &[Point<2>]) -> impl Iterator<Item = (&Point<2>, &Point<2>)> {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .take(polygon.len())
+}
+
+/// How often the polygon winds around the point counter clockwise. 0 means outside.
+/// Points on the boundary have no winding number, check them with [`locate`].
+pub fn winding_number(p: &Point<2>, polygon: &[Point<2>]) -> i32 {
+    let mut winding = 0;
+    for (a, b) in edges(polygon) {
+        if a[1] <= p[1] {
+            if b[1] > p[1] && orientation(a, b, p).is_gt() {
+                winding += 1;
+            }
+        } else if b[1] <= p[1] && orientation(a, b, p).is_lt() {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+/// Where the point is, relative to the polygon
+pub fn locate(p: &Point<2>, polygon: &[Point<2>]) -> Location {
+    if edges(polygon).any(|(a, b)| on_segment(p, a, b)) {
+        Location::Boundary
+    } else if winding_number(p, polygon) != 0 {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Twice the signed area, positive if the vertices go counter clockwise
+pub fn signed_area2(polygon: &[Point<2>]) -> i128 {
+    edges(polygon)
+        .map(|(a, b)| i128::from(a[0]) * i128::from(b[1]) - i128::from(b[0]) * i128::from(a[1]))
+        .sum()
+}
+
+/// Fails if two edges that aren't neighbours touch, or if neighbouring edges overlap. O(n²).
+pub fn check_simple(polygon: &[Point<2>]) -> Result<(), String> {
+    if polygon.len() < 3 || signed_area2(polygon) == 0 {
+        return Err("The polygon has no area".to_string());
+    }
+    let edges: Vec<_> = edges(polygon).collect();
+    let n = edges.len();
+    for i in 0..n {
+        let (a, b) = edges[i];
+        // Neighbours share a vertex, they may only overlap if they fold back onto each other
+        let (_, next) = edges[(i + 1) % n];
+        if orientation(a, b, next).is_eq() && (on_segment(next, a, b) || on_segment(a, b, next)) {
+            return Err(format!("The edges at {b} fold back onto each other"));
+        }
+        for (j, &(c, d)) in edges.iter().enumerate().skip(i + 2) {
+            let neighbours = i == 0 && j == n - 1;
+            if !neighbours && segments_intersect(a, b, c, d) {
+                return Err(format!("The edges {a} to {b} and {c} to {d} intersect"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A simple polygon, with its vertices stored counter clockwise
+pub struct SimplePolygon {
+    vertices: Vec<Point<2>>,
+}
+
+impl SimplePolygon {
+    pub fn new(mut vertices: Vec<Point<2>>) -> Result<Self, String> {
+        check_simple(&vertices)?;
+        if signed_area2(&vertices) < 0 {
+            vertices.reverse();
+        }
+        Ok(SimplePolygon { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Point<2>] {
+        &self.vertices
+    }
+
+    pub fn locate(&self, p: &Point<2>) -> Location {
+        locate(p, &self.vertices)
+    }
+
+    /// Is the closed rectangle with the opposite corners `a` and `b` inside the closed polygon
+    pub fn contains_rect(&self, a: &Point<2>, b: &Point<2>) -> bool {
+        if a[0] == b[0] || a[1] == b[1] {
+            return self.contains_segment(a, b);
+        }
+        let (min, max) = (
+            Point([a[0].min(b[0]), a[1].min(b[1])]),
+            Point([a[0].max(b[0]), a[1].max(b[1])]),
+        );
+        // If no edge reaches into the open rectangle, it's either completely inside or outside.
+        // Its center decides which, in doubled coordinates so it's an integer point.
+        if edges(&self.vertices).any(|(c, d)| segment_enters_box(c, d, &min, &max)) {
+            return false;
+        }
+        let center = Point([min[0] + max[0], min[1] + max[1]]);
+        let doubled: Vec<Point<2>> = self
+            .vertices
+            .iter()
+            .map(|v| Point(v.0.map(|c| 2 * c)))
+            .collect();
+        locate(&center, &doubled) != Location::Outside
+    }
+
+    /// Is every point of the segment inside the closed polygon
+    pub fn contains_segment(&self, p: &Point<2>, q: &Point<2>) -> bool {
+        if edges(&self.vertices).any(|(a, b)| segments_cross(p, q, a, b)) {
+            return false;
+        }
+        // The segment can only leave the polygon where it touches a vertex or at its ends,
+        // so it's enough to check the directions it goes from there
+        let (forward, backward) = (
+            Point([q[0] - p[0], q[1] - p[1]]),
+            Point([p[0] - q[0], p[1] - q[1]]),
+        );
+        if p == q {
+            return self.locate(p) != Location::Outside;
+        }
+        self.leaves_towards(p, &forward)
+            && self.leaves_towards(q, &backward)
+            && self
+                .vertices
+                .iter()
+                .filter(|v| *v != p && *v != q && on_segment(v, p, q))
+                .all(|v| self.leaves_towards(v, &forward) && self.leaves_towards(v, &backward))
+    }
+
+    /// Does a short step from `p` into `direction` stay inside the closed polygon
+    fn leaves_towards(&self, p: &Point<2>, direction: &Point<2>) -> bool {
+        let origin = Point([0, 0]);
+        let n = self.vertices.len();
+        if let Some(i) = self.vertices.iter().position(|v| v == p) {
+            // The interior is the cone from the outgoing edge counter clockwise to the incoming edge
+            let (prev, next) = (&self.vertices[(i + n - 1) % n], &self.vertices[(i + 1) % n]);
+            let out = Point([next[0] - p[0], next[1] - p[1]]);
+            let back = Point([prev[0] - p[0], prev[1] - p[1]]);
+            return if orientation(&origin, &out, &back).is_ge() {
+                orientation(&origin, &out, direction).is_ge()
+                    && orientation(&origin, direction, &back).is_ge()
+            } else {
+                !(orientation(&origin, &back, direction).is_gt()
+                    && orientation(&origin, direction, &out).is_gt())
+            };
+        }
+        match edges(&self.vertices).find(|(a, b)| on_segment(p, a, b)) {
+            // The interior is left of every edge
+            Some((a, b)) => {
+                let edge = Point([b[0] - a[0], b[1] - a[1]]);
+                orientation(&origin, &edge, direction).is_ge()
+            }
+            None => self.locate(p) == Location::Inside,
+        }
+    }
+
+    /// The largest rectangle that has two vertices as opposite corners and is completely inside.
+    /// Returns the two vertices. Polygons with only horizontal and vertical edges take a faster path.
+    pub fn largest_rectangle(&self) -> Option<(Point<2>, Point<2>)> {
+        // In doubled coordinates, the tiles of the rectilinear polygon also cover the gaps between
+        // edges, so covered tiles mean the rectangle is inside
+        let doubled: Vec<Point<2>> = self
+            .vertices
+            .iter()
+            .map(|v| Point(v.0.map(|c| 2 * c)))
+            .collect();
+        if let Ok(rectilinear) = RectilinearPolygon::new(&doubled) {
+            let double = |p: &Point<2>| Point(p.0.map(|c| 2 * c));
+            return self
+                .largest_rectangle_by(|a, b| rectilinear.contains_rect(&double(a), &double(b)));
+        }
+        self.largest_rectangle_by(|a, b| self.contains_rect(a, b))
+    }
+
+    /// The largest rectangle between two vertices that `contains` accepts
+    fn largest_rectangle_by(
+        &self,
+        contains: impl Fn(&Point<2>, &Point<2>) -> bool + Sync,
+    ) -> Option<(Point<2>, Point<2>)> {
+        let area = |(a, b): &(Point<2>, Point<2>)| {
+            (u128::from(a[0].abs_diff(b[0])) + 1) * (u128::from(a[1].abs_diff(b[1])) + 1)
+        };
+        (0..self.vertices.len())
+            .into_par_iter()
+            .flat_map_iter(|a| {
+                (a + 1..self.vertices.len()).map(move |b| (self.vertices[a], self.vertices[b]))
+            })
+            .filter(|(a, b)| contains(a, b))
+            .max_by_key(|pair| (area(pair), std::cmp::Reverse(*pair)))
+    }
+}
+
+/// Does the closed segment `c`-`d` share a point with the open box between `min` and `max`
+fn segment_enters_box(c: &Point<2>, d: &Point<2>, min: &Point<2>, max: &Point<2>) -> bool {
+    // Separating axis test: the box axes, then the line through the segment
+    if c[0].max(d[0]) <= min[0] || c[0].min(d[0]) >= max[0] {
+        return false;
+    }
+    if c[1].max(d[1]) <= min[1] || c[1].min(d[1]) >= max[1] {
+        return false;
+    }
+    let corners = [
+        Point([min[0], min[1]]),
+        Point([max[0], min[1]]),
+        Point([max[0], max[1]]),
+        Point([min[0], max[1]]),
+    ];
+    let sides = corners.map(|corner| orientation(c, d, &corner));
+    !(sides.iter().all(|s| s.is_ge()) || sides.iter().all(|s| s.is_le()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[[i64; 2]]) -> Vec<Point<2>> {
+        coords.iter().copied().map(Point).collect()
+    }
+
+    #[test]
+    fn test_segments() {
+        let [a, b, c, d, e] = [[0, 0], [4, 4], [0, 4], [4, 0], [6, 6]].map(Point);
+        assert!(segments_intersect(&a, &b, &c, &d));
+        assert!(segments_cross(&a, &b, &c, &d));
+        // Collinear overlap and touching ends intersect, but don't cross
+        assert!(segments_intersect(&a, &e, &b, &Point([8, 8])));
+        assert!(segments_intersect(&a, &b, &b, &d));
+        assert!(!segments_cross(&a, &b, &b, &d));
+        assert!(!segments_intersect(&a, &b, &Point([5, 5]), &e));
+        assert!(!segments_intersect(&a, &c, &d, &b));
+        assert_eq!(orientation(&a, &d, &b), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_locate() {
+        // A triangle going clockwise and a square with a notch
+        let triangle = points(&[[0, 0], [0, 10], [10, 0]]);
+        assert_eq!(locate(&Point([2, 2]), &triangle), Location::Inside);
+        assert_eq!(winding_number(&Point([2, 2]), &triangle), -1);
+        assert_eq!(locate(&Point([5, 5]), &triangle), Location::Boundary);
+        assert_eq!(locate(&Point([6, 6]), &triangle), Location::Outside);
+
+        let notched = points(&[[0, 0], [4, 0], [2, 2], [4, 4], [0, 4]]);
+        assert_eq!(locate(&Point([3, 2]), &notched), Location::Outside);
+        assert_eq!(locate(&Point([1, 2]), &notched), Location::Inside);
+        assert_eq!(locate(&Point([2, 2]), &notched), Location::Boundary);
+    }
+
+    #[test]
+    fn test_check_simple() {
+        assert!(check_simple(&points(&[[0, 0], [4, 0], [4, 4], [0, 4]])).is_ok());
+        assert!(check_simple(&points(&[[0, 0], [4, 4], [4, 0], [0, 4]])).is_err());
+        assert!(check_simple(&points(&[[0, 0], [4, 0], [2, 0], [2, 3]])).is_err());
+        assert!(check_simple(&points(&[[0, 0], [4, 0], [8, 0]])).is_err());
+    }
+
+    #[test]
+    fn test_contains_rect() {
+        // A diamond, only rectangles around the middle fit
+        let diamond = SimplePolygon::new(points(&[[4, 0], [8, 4], [4, 8], [0, 4]])).unwrap();
+        assert!(diamond.contains_rect(&Point([2, 2]), &Point([6, 6])));
+        assert!(!diamond.contains_rect(&Point([1, 2]), &Point([6, 6])));
+        assert!(diamond.contains_rect(&Point([0, 4]), &Point([8, 4])));
+        assert!(!diamond.contains_rect(&Point([0, 3]), &Point([8, 3])));
+
+        // A one wide gap between two arms, the rectangle over it is outside
+        let u = SimplePolygon::new(points(&[
+            [0, 0],
+            [2, 0],
+            [2, 5],
+            [3, 5],
+            [3, 0],
+            [5, 0],
+            [5, 7],
+            [0, 7],
+        ]))
+        .unwrap();
+        assert!(!u.contains_rect(&Point([0, 0]), &Point([5, 5])));
+        assert!(u.contains_rect(&Point([0, 5]), &Point([5, 7])));
+        assert!(!u.contains_segment(&Point([0, 3]), &Point([5, 3])));
+        assert!(u.contains_segment(&Point([2, 5]), &Point([3, 5])));
+    }
+
+    #[test]
+    fn test_largest_rectangle() {
+        // The rectilinear fast path and the general check agree
+        let u = SimplePolygon::new(points(&[
+            [0, 0],
+            [2, 0],
+            [2, 5],
+            [3, 5],
+            [3, 0],
+            [5, 0],
+            [5, 7],
+            [0, 7],
+        ]))
+        .unwrap();
+        let (a, b) = u.largest_rectangle().unwrap();
+        assert!(u.contains_rect(&a, &b));
+        assert_eq!((a, b), (Point([2, 0]), Point([0, 7])));
+        assert!(!u.contains_rect(&Point([0, 0]), &Point([3, 7])));
+
+        // The day 9 example, it takes the fast path
+        let example = SimplePolygon::new(points(&[
+            [7, 1],
+            [11, 1],
+            [11, 7],
+            [9, 7],
+            [9, 5],
+            [2, 5],
+            [2, 3],
+            [7, 3],
+        ]))
+        .unwrap();
+        let (a, b) = example.largest_rectangle().unwrap();
+        assert_eq!((a[0].abs_diff(b[0]) + 1) * (a[1].abs_diff(b[1]) + 1), 24);
+        assert!(example.contains_rect(&Point([9, 5]), &Point([2, 3])));
+        assert!(!example.contains_rect(&Point([11, 1]), &Point([2, 5])));
+
+        let hexagon =
+            SimplePolygon::new(points(&[[2, 0], [6, 0], [8, 3], [6, 6], [2, 6], [0, 3]])).unwrap();
+        assert_eq!(
+            hexagon.largest_rectangle(),
+            Some((Point([2, 0]), Point([6, 6])))
+        );
+    }
+
+    /// Checks every point of the rectangle on a grid of quarter steps. Not exact in general, but
+    /// enough for the small polygons of the tests.
+    fn contains_rect_sampled(polygon: &SimplePolygon, a: &Point<2>, b: &Point<2>) -> bool {
+        let scaled: Vec<Point<2>> = polygon
+            .vertices()
+            .iter()
+            .map(|v| Point(v.0.map(|c| 4 * c)))
+            .collect();
+        (4 * a[0].min(b[0])..=4 * a[0].max(b[0])).all(|x| {
+            (4 * a[1].min(b[1])..=4 * a[1].max(b[1]))
+                .all(|y| locate(&Point([x, y]), &scaled) != Location::Outside)
+        })
+    }
+
+    #[test]
+    fn test_general_path() {
+        let rectilinear = [
+            // The day 9 example
+            points(&[
+                [7, 1],
+                [11, 1],
+                [11, 7],
+                [9, 7],
+                [9, 5],
+                [2, 5],
+                [2, 3],
+                [7, 3],
+            ]),
+            // A one wide gap between two arms
+            points(&[
+                [0, 0],
+                [2, 0],
+                [2, 5],
+                [3, 5],
+                [3, 0],
+                [5, 0],
+                [5, 7],
+                [0, 7],
+            ]),
+        ];
+        let diagonal = [
+            // An arrow with a notch at the back
+            points(&[[0, 0], [6, 0], [9, 4], [6, 8], [0, 8], [3, 4]]),
+            // A bowl with slanted walls and a spike in the middle
+            points(&[
+                [0, 0],
+                [10, 0],
+                [10, 8],
+                [8, 8],
+                [6, 2],
+                [5, 6],
+                [4, 2],
+                [2, 8],
+                [0, 8],
+            ]),
+        ];
+        for vertices in rectilinear.into_iter().chain(diagonal) {
+            let polygon = SimplePolygon::new(vertices).unwrap();
+            // Every rectangle between integer points of the bounding box
+            let (max_x, max_y) = polygon
+                .vertices()
+                .iter()
+                .fold((0, 0), |(x, y), v| (v[0].max(x), v[1].max(y)));
+            let corners: Vec<Point<2>> = (0..=max_x)
+                .flat_map(|x| (0..=max_y).map(move |y| Point([x, y])))
+                .collect();
+            for a in &corners {
+                for b in corners.iter().filter(|b| b >= &a) {
+                    assert_eq!(
+                        polygon.contains_rect(a, b),
+                        contains_rect_sampled(&polygon, a, b),
+                        "{a:?} {b:?} in {:?}",
+                        polygon.vertices()
+                    );
+                }
+            }
+            let general = polygon.largest_rectangle_by(|a, b| polygon.contains_rect(a, b));
+            assert_eq!(
+                general,
+                polygon.largest_rectangle_by(|a, b| contains_rect_sampled(&polygon, a, b))
+            );
+            // The fast path of the rectilinear ones agrees too
+            assert_eq!(polygon.largest_rectangle(), general);
+        }
+    }
+}
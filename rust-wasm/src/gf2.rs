@@ -0,0 +1,374 @@
+//! Linear algebra over GF(2), where adding is xor. Toggling lights is exactly this: pressing a
+//! button twice does nothing, and the order of the presses doesn't matter.
+//!
+//! The bits are packed into `u64` words, so adding two rows xors 64 entries at once.
+
+use std::ops::BitXorAssign;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVector {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVector {
+    pub fn zeros(len: usize) -> Self {
+        BitVector {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "Bit {i} out of range for length {}", self.len);
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "Bit {i} out of range for length {}", self.len);
+        let mask = 1 << (i % 64);
+        if value {
+            self.words[i / 64] |= mask;
+        } else {
+            self.words[i / 64] &= !mask;
+        }
+    }
+
+    pub fn toggle(&mut self, i: usize) {
+        assert!(i < self.len, "Bit {i} out of range for length {}", self.len);
+        self.words[i / 64] ^= 1 << (i % 64);
+    }
+
+    /// Number of set bits, the Hamming weight
+    pub fn count_ones(&self) -> u32 {
+        self.words.iter().map(|w| w.count_ones()).sum()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Indices of the set bits, in increasing order
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+impl BitXorAssign<&BitVector> for BitVector {
+    fn bitxor_assign(&mut self, rhs: &BitVector) {
+        assert_eq!(self.len, rhs.len, "Can't add vectors of different lengths");
+        for (a, b) in self.words.iter_mut().zip(&rhs.words) {
+            *a ^= b;
+        }
+    }
+}
+
+impl FromIterator<bool> for BitVector {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let bits: Vec<bool> = iter.into_iter().collect();
+        let mut vector = BitVector::zeros(bits.len());
+        for (i, _) in bits.iter().enumerate().filter(|(_, bit)| **bit) {
+            vector.set(i, true);
+        }
+        vector
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    rows: Vec<BitVector>,
+    columns: usize,
+}
+
+impl BitMatrix {
+    pub fn zeros(rows: usize, columns: usize) -> Self {
+        BitMatrix {
+            rows: vec![BitVector::zeros(columns); rows],
+            columns,
+        }
+    }
+
+    /// Fails if the rows don't all have the same length
+    pub fn from_rows(rows: Vec<BitVector>) -> Result<Self, String> {
+        let columns = rows.first().map_or(0, BitVector::len);
+        if let Some(row) = rows.iter().position(|row| row.len() != columns) {
+            return Err(format!(
+                "Row {row} has {} columns, expected {columns}",
+                rows[row].len()
+            ));
+        }
+        Ok(BitMatrix { rows, columns })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn row(&self, row: usize) -> &BitVector {
+        &self.rows[row]
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> bool {
+        self.rows[row].get(column)
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: bool) {
+        self.rows[row].set(column, value);
+    }
+
+    /// Brings the matrix into reduced row echelon form and returns the pivot column of each
+    /// non zero row. The zero rows end up at the bottom.
+    pub fn reduce(&mut self) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        for column in 0..self.columns {
+            let next = pivots.len();
+            let Some(found) = (next..self.rows()).find(|&r| self.rows[r].get(column)) else {
+                continue;
+            };
+            self.rows.swap(next, found);
+            let pivot = self.rows[next].clone();
+            for (r, row) in self.rows.iter_mut().enumerate() {
+                if r != next && row.get(column) {
+                    *row ^= &pivot;
+                }
+            }
+            pivots.push(column);
+        }
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().reduce().len()
+    }
+
+    /// A basis of all `x` with `self * x = 0`, one vector per free column
+    pub fn null_space(&self) -> Vec<BitVector> {
+        let mut reduced = self.clone();
+        let pivots = reduced.reduce();
+        reduced.reduced_null_space(&pivots, self.columns)
+    }
+
+    /// The null space of the first `columns` columns, for a matrix that was already reduced and
+    /// has these `pivots` in them
+    fn reduced_null_space(&self, pivots: &[usize], columns: usize) -> Vec<BitVector> {
+        (0..columns)
+            .filter(|column| !pivots.contains(column))
+            .map(|free| {
+                // Setting the free column forces every pivot that depends on it
+                let mut vector = BitVector::zeros(columns);
+                vector.set(free, true);
+                for (row, &pivot) in pivots.iter().enumerate() {
+                    if self.get(row, free) {
+                        vector.set(pivot, true);
+                    }
+                }
+                vector
+            })
+            .collect()
+    }
+
+    /// All `x` with `self * x = target`, or `None` if there is none
+    pub fn solve(&self, target: &BitVector) -> Option<Solutions> {
+        assert_eq!(
+            target.len(),
+            self.rows(),
+            "The target needs one bit per row"
+        );
+        // Reduce the matrix with the target as an extra column
+        let mut augmented = BitMatrix::zeros(self.rows(), self.columns + 1);
+        for (r, row) in self.rows.iter().enumerate() {
+            for c in row.ones() {
+                augmented.set(r, c, true);
+            }
+            augmented.set(r, self.columns, target.get(r));
+        }
+        let pivots = augmented.reduce();
+        if pivots.last() == Some(&self.columns) {
+            return None;
+        }
+
+        // The free columns are zero, so every pivot is just its part of the target
+        let mut particular = BitVector::zeros(self.columns);
+        for (row, &pivot) in pivots.iter().enumerate() {
+            particular.set(pivot, augmented.get(row, self.columns));
+        }
+        // The target column has no pivot, so the rest is the reduced matrix itself
+        Some(Solutions {
+            particular,
+            null_space: augmented.reduced_null_space(&pivots, self.columns),
+        })
+    }
+}
+
+/// Every solution is the particular one plus a combination of the null space
+pub struct Solutions {
+    pub particular: BitVector,
+    pub null_space: Vec<BitVector>,
+}
+
+/// Solution sets with a larger dimension are not enumerated, that would take too long
+pub const MAX_ENUMERATED_DIMENSION: usize = 32;
+
+impl Solutions {
+    /// Number of solutions is `2^dimension`
+    pub fn dimension(&self) -> usize {
+        self.null_space.len()
+    }
+
+    fn check_enumerable(&self) -> Result<(), String> {
+        if self.dimension() > MAX_ENUMERATED_DIMENSION {
+            return Err(format!(
+                "There are 2^{} solutions, too many to enumerate",
+                self.dimension()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Every solution, in Gray code order so each one only needs a single xor. Fails if the
+    /// dimension is above [`MAX_ENUMERATED_DIMENSION`].
+    pub fn iter(&self) -> Result<impl Iterator<Item = BitVector> + '_, String> {
+        self.check_enumerable()?;
+        let mut current = self.particular.clone();
+        Ok((0..1u64 << self.dimension()).map(move |i| {
+            if i > 0 {
+                current ^= &self.null_space[i.trailing_zeros() as usize];
+            }
+            current.clone()
+        }))
+    }
+
+    /// A solution with the fewest ones. Fails if the dimension is above
+    /// [`MAX_ENUMERATED_DIMENSION`].
+    pub fn min_weight(&self) -> Result<BitVector, String> {
+        self.check_enumerable()?;
+        let mut current = self.particular.clone();
+        let mut best = current.clone();
+        for i in 1..1u64 << self.dimension() {
+            current ^= &self.null_space[i.trailing_zeros() as usize];
+            if current.count_ones() < best.count_ones() {
+                best.clone_from(&current);
+            }
+        }
+        Ok(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
+
+    fn matrix(rows: &[&str]) -> BitMatrix {
+        BitMatrix::from_rows(
+            rows.iter()
+                .map(|row| row.chars().map(|c| c == '1').collect())
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn multiply(matrix: &BitMatrix, x: &BitVector) -> BitVector {
+        (0..matrix.rows())
+            .map(|r| x.ones().filter(|&c| matrix.get(r, c)).count() % 2 == 1)
+            .collect()
+    }
+
+    #[test]
+    fn test_bit_vector() {
+        let mut vector = BitVector::zeros(130);
+        vector.set(0, true);
+        vector.set(64, true);
+        vector.toggle(129);
+        assert_eq!(vector.count_ones(), 3);
+        assert_eq!(vector.ones().collect::<Vec<_>>(), vec![0, 64, 129]);
+        vector ^= &vector.clone();
+        assert!(vector.is_zero());
+    }
+
+    #[test]
+    fn test_rank_and_null_space() {
+        let m = matrix(&["1100", "0110", "1010"]);
+        assert_eq!(m.rank(), 2);
+        let null_space = m.null_space();
+        assert_eq!(null_space.len(), 2);
+        for x in &null_space {
+            assert!(multiply(&m, x).is_zero());
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        // The first machine of the day 10 example, one column per button
+        let m = matrix(&["000011", "010001", "001110", "110100"]);
+        let target: BitVector = [false, true, true, false].into_iter().collect();
+        let solutions = m.solve(&target).unwrap();
+        assert_eq!(solutions.dimension(), 2);
+        assert!(
+            solutions
+                .iter()
+                .unwrap()
+                .all(|x| multiply(&m, &x) == target)
+        );
+        assert_eq!(solutions.min_weight().unwrap().count_ones(), 2);
+
+        let target: BitVector = [true, false, false, false].into_iter().collect();
+        assert!(matrix(&["1", "1", "0", "0"]).solve(&target).is_none());
+    }
+
+    #[test]
+    fn test_too_many_solutions() {
+        // 70 buttons that all toggle the same light
+        let m = BitMatrix::from_rows(vec![(0..70).map(|_| true).collect()]).unwrap();
+        let target: BitVector = [true].into_iter().collect();
+        let solutions = m.solve(&target).unwrap();
+        assert_eq!(solutions.dimension(), 69);
+        assert!(solutions.min_weight().is_err());
+        assert!(solutions.iter().is_err());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_min_weight_matches_brute_force(
+            rows in prop::collection::vec(prop::collection::vec(any::<bool>(), 7), 1..6),
+            target in prop::collection::vec(any::<bool>(), 6),
+        ) {
+            let m = BitMatrix::from_rows(rows.into_iter().map(|r| r.into_iter().collect()).collect()).unwrap();
+            let target: BitVector = target[..m.rows()].iter().copied().collect();
+            let brute_force = (0..1u32 << m.columns())
+                .map(|bits| (0..m.columns()).map(|c| bits >> c & 1 == 1).collect::<BitVector>())
+                .filter(|x| multiply(&m, x) == target)
+                .map(|x| x.count_ones())
+                .min();
+            let solutions = m.solve(&target);
+            prop_assert_eq!(solutions.as_ref().map(|s| s.min_weight().unwrap().count_ones()), brute_force);
+            if let Some(solutions) = solutions {
+                prop_assert_eq!(solutions.dimension(), m.columns() - m.rank());
+            }
+        }
+    }
+}
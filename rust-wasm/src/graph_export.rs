@@ -0,0 +1,197 @@
+//! Writes a directed graph as DOT for Graphviz, as GraphML, or as JSON in the node-link format,
+//! so the website can draw the graph of the user's own input.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Dot,
+    GraphMl,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" => Ok(Format::Dot),
+            "graphml" => Ok(Format::GraphMl),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "Unknown format '{s}', available are dot, graphml and json"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Node {
+    pub id: String,
+    /// Number of paths from the start to this node. A string, because JavaScript numbers lose
+    /// precision above 2^53.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paths: Option<String>,
+    /// Fill colour of highlighted nodes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Link {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Graph {
+    pub directed: bool,
+    pub nodes: Vec<Node>,
+    pub links: Vec<Link>,
+}
+
+impl Graph {
+    pub fn export(&self, format: Format) -> String {
+        match format {
+            Format::Dot => self.to_dot(),
+            Format::GraphMl => self.to_graphml(),
+            Format::Json => self.to_json(),
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        for node in &self.nodes {
+            let id = escape_dot(&node.id);
+            let _ = write!(dot, "    \"{id}\" [label = \"{id}");
+            if let Some(paths) = &node.paths {
+                let _ = write!(dot, "\\n{}", escape_dot(paths));
+            }
+            dot += "\"";
+            if let Some(colour) = &node.highlight {
+                let _ = write!(
+                    dot,
+                    ", shape = \"box\", style = \"filled\", fillcolor = \"{}\"",
+                    escape_dot(colour)
+                );
+            }
+            dot += "]\n";
+        }
+        for link in &self.links {
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\"",
+                escape_dot(&link.source),
+                escape_dot(&link.target)
+            );
+        }
+        dot += "}\n";
+        dot
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"paths\" for=\"node\" attr.name=\"paths\" attr.type=\"string\"/>\n",
+            "  <key id=\"highlight\" for=\"node\" attr.name=\"highlight\" attr.type=\"string\"/>\n",
+        ));
+        let direction = if self.directed {
+            "directed"
+        } else {
+            "undirected"
+        };
+        let _ = writeln!(xml, "  <graph edgedefault=\"{direction}\">");
+        for node in &self.nodes {
+            let _ = write!(xml, "    <node id=\"{}\">", escape_xml(&node.id));
+            for (key, value) in [("paths", &node.paths), ("highlight", &node.highlight)] {
+                if let Some(value) = value {
+                    let _ = write!(xml, "<data key=\"{key}\">{}</data>", escape_xml(value));
+                }
+            }
+            xml += "</node>\n";
+        }
+        for link in &self.links {
+            let _ = writeln!(
+                xml,
+                "    <edge source=\"{}\" target=\"{}\"/>",
+                escape_xml(&link.source),
+                escape_xml(&link.target)
+            );
+        }
+        xml += "  </graph>\n</graphml>\n";
+        xml
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("The graph is always serializable")
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> Graph {
+        let node = |id: &str, paths: Option<u32>, highlight: Option<&str>| Node {
+            id: id.to_string(),
+            paths: paths.map(|p| p.to_string()),
+            highlight: highlight.map(str::to_string),
+        };
+        Graph {
+            directed: true,
+            nodes: vec![
+                node("you", Some(1), Some("green")),
+                node("a&b", None, None),
+                node("out", Some(1), Some("red")),
+            ],
+            links: vec![
+                Link {
+                    source: "you".to_string(),
+                    target: "out".to_string(),
+                },
+                Link {
+                    source: "a&b".to_string(),
+                    target: "out".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_formats() {
+        assert_eq!("GraphML".parse(), Ok(Format::GraphMl));
+        assert!("png".parse::<Format>().is_err());
+
+        let dot = graph().to_dot();
+        assert!(dot.contains(
+            r#""you" [label = "you\n1", shape = "box", style = "filled", fillcolor = "green"]"#
+        ));
+        assert!(dot.contains(r#""a&b" -> "out""#));
+
+        let xml = graph().to_graphml();
+        assert!(xml.contains(r#"<node id="a&amp;b"></node>"#));
+        assert!(xml.contains(
+            r#"<node id="out"><data key="paths">1</data><data key="highlight">red</data></node>"#
+        ));
+
+        assert_eq!(
+            graph().to_json(),
+            r#"{"directed":true,"nodes":[{"id":"you","paths":"1","highlight":"green"},{"id":"a&b"},{"id":"out","paths":"1","highlight":"red"}],"links":[{"source":"you","target":"out"},{"source":"a&b","target":"out"}]}"#
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! A rectangular 2D grid, like the character maps many puzzles use.
+//!
+//! Positions are `(row, col)`, the same order `ndarray` uses. Row 0 is the first line of the input.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use ndarray::Array2;
+use nom::{
+    IResult, Parser,
+    character::complete::{anychar, line_ending},
+    combinator::map_opt,
+    multi::{many1, separated_list1},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    /// Row major
+    cells: Vec<T>,
+}
+
+/// Offsets of the 4 orthogonal neighbours
+const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Offsets of all 8 neighbours, including the diagonals
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+impl<T> Grid<T> {
+    /// Returns `None` if the rows have different lengths or there are no cells
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first()?.len();
+        if width == 0 || rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Builds a grid from a function of the position
+    pub fn from_fn(height: usize, width: usize, f: impl FnMut((usize, usize)) -> T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: (0..height)
+                .flat_map(|row| (0..width).map(move |col| (row, col)))
+                .map(f)
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): (usize, usize)) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, (row, col): (usize, usize)) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // Empty grids can have a width of 0
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "Column {col} is outside the grid");
+        self.cells[col..].iter().step_by(self.width)
+    }
+
+    /// All cells in row major order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// All cells with their position, in row major order
+    pub fn positions(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| ((i / self.width, i % self.width), cell))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// The orthogonal neighbours of a position that are inside the grid
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(pos, &NEIGHBOURS_4)
+    }
+
+    /// All neighbours of a position, including diagonals, that are inside the grid
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(pos, &NEIGHBOURS_8)
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        (row, col): (usize, usize),
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |&(dr, dc)| {
+            let pos = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+            (pos.0 < self.height && pos.1 < self.width).then_some(pos)
+        })
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(height: usize, width: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Like [`Grid::from_rows`], but pads short rows with `fill` instead of rejecting them
+    pub fn from_ragged_rows(rows: Vec<Vec<T>>, fill: T) -> Option<Self> {
+        let width = rows.iter().map(Vec::len).max()?;
+        let rows = rows
+            .into_iter()
+            .map(|mut row| {
+                row.resize(width, fill.clone());
+                row
+            })
+            .collect();
+        Self::from_rows(rows)
+    }
+
+    /// Swaps rows and columns
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.width, self.height, |(row, col)| {
+            self[(col, row)].clone()
+        })
+    }
+
+    /// Rotates by 90° clockwise
+    pub fn rotate_cw(&self) -> Self {
+        Self::from_fn(self.width, self.height, |(row, col)| {
+            self[(self.height - 1 - col, row)].clone()
+        })
+    }
+
+    /// Rotates by 90° counter clockwise
+    pub fn rotate_ccw(&self) -> Self {
+        Self::from_fn(self.width, self.height, |(row, col)| {
+            self[(col, self.width - 1 - row)].clone()
+        })
+    }
+
+    /// Mirrors left and right
+    pub fn flip_horizontal(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(row, col)| {
+            self[(row, self.width - 1 - col)].clone()
+        })
+    }
+
+    /// Mirrors top and bottom
+    pub fn flip_vertical(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(row, col)| {
+            self[(self.height - 1 - row, col)].clone()
+        })
+    }
+
+    /// All 8 rotations and reflections, starting with the grid itself. Symmetric grids contain duplicates.
+    pub fn orientations(&self) -> [Self; 8] {
+        let r1 = self.rotate_cw();
+        let r2 = r1.rotate_cw();
+        let r3 = r2.rotate_cw();
+        let flipped = [self, &r1, &r2, &r3].map(Grid::flip_horizontal);
+        let [f0, f1, f2, f3] = flipped;
+        [self.clone(), r1, r2, r3, f0, f1, f2, f3]
+    }
+
+    pub fn to_array2(&self) -> Array2<T> {
+        Array2::from_shape_vec((self.height, self.width), self.cells.clone())
+            .expect("Grid has a valid shape")
+    }
+}
+
+impl<T: Clone> From<Array2<T>> for Grid<T> {
+    fn from(array: Array2<T>) -> Self {
+        let (height, width) = array.dim();
+        Grid {
+            width,
+            height,
+            cells: array.iter().cloned().collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(
+            row < self.height && col < self.width,
+            "({row}, {col}) is outside the grid"
+        );
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.height && col < self.width,
+            "({row}, {col}) is outside the grid"
+        );
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+/// Prints every row on its own line
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses lines of characters into a rectangular grid. Every character is mapped with `cell`,
+/// the grid ends at the first character that doesn't map to a cell.
+pub fn grid<'a, T>(
+    cell: impl Fn(char) -> Option<T> + Copy,
+) -> impl Parser<&'a str, Output = Grid<T>, Error = nom::error::Error<&'a str>> {
+    let row = many1(map_opt(anychar, cell));
+    map_opt(separated_list1(line_ending, row), Grid::from_rows)
+}
+
+/// Parses a whole grid, see [`grid`]
+pub fn parse_grid<T>(
+    input: &str,
+    cell: impl Fn(char) -> Option<T> + Copy,
+) -> IResult<&str, Grid<T>> {
+    grid(cell).parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        parse_grid("abc\ndef", |c| c.is_ascii_alphabetic().then_some(c))
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn test_parse() {
+        let (rest, grid) = parse_grid("#.#\n.#.\n\nrest", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(rest, "\n\nrest");
+        assert_eq!((grid.height(), grid.width()), (2, 3));
+        assert_eq!(grid.row(1), &[false, true, false]);
+
+        // Rows with different lengths are rejected
+        assert!(parse_grid("##\n#", |c| (c == '#').then_some(())).is_err());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::filled(3, 3, 0);
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbours8((2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = example();
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+
+        let orientations = grid.orientations();
+        assert_eq!(orientations[0], grid);
+        assert!(orientations.contains(&grid.transpose()));
+        assert!(orientations.contains(&grid.flip_vertical()));
+    }
+
+    #[test]
+    fn test_array2() {
+        let grid = example();
+        let array = grid.to_array2();
+        assert_eq!(array[[1, 0]], 'd');
+        assert_eq!(Grid::from(array), grid);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+    }
+}
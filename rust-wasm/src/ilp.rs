@@ -0,0 +1,474 @@
+//! Exact integer linear programming for small systems of equations, like the machines of day 10.
+//!
+//! Minimises `costs · x` subject to `equations · x = rhs` and `x >= 0`, with `x` integer.
+//! The equations are brought into reduced row echelon form with integer arithmetic, so every
+//! pivot variable is an exact function of the free variables. Only the free variables are then
+//! searched, depth first within their bounds. A branch is cut as soon as a pivot variable can't be
+//! in range anymore, or the cost can't beat the best solution found so far.
+
+pub struct IntegerProgram {
+    equations: Vec<Vec<i64>>,
+    rhs: Vec<i64>,
+    costs: Vec<i64>,
+}
+
+impl IntegerProgram {
+    /// One row of coefficients and one right hand side per equation, one cost per variable.
+    /// The costs may not be negative, so a partial assignment gives a lower bound.
+    pub fn new(equations: Vec<Vec<i64>>, rhs: Vec<i64>, costs: Vec<i64>) -> Result<Self, String> {
+        if equations.len() != rhs.len() {
+            return Err(format!(
+                "{} equations but {} right hand sides",
+                equations.len(),
+                rhs.len()
+            ));
+        }
+        if let Some(row) = equations.iter().position(|row| row.len() != costs.len()) {
+            return Err(format!(
+                "Equation {row} has {} coefficients but there are {} variables",
+                equations[row].len(),
+                costs.len()
+            ));
+        }
+        if let Some(var) = costs.iter().position(|&cost| cost < 0) {
+            return Err(format!("Variable {var} has a negative cost"));
+        }
+        Ok(IntegerProgram {
+            equations,
+            rhs,
+            costs,
+        })
+    }
+
+    pub fn variables(&self) -> usize {
+        self.costs.len()
+    }
+
+    /// Total cost of an assignment
+    pub fn cost(&self, values: &[u64]) -> u64 {
+        self.costs
+            .iter()
+            .zip(values)
+            .map(|(&cost, &value)| cost as u64 * value)
+            .sum()
+    }
+
+    /// Largest value of each variable. An equation with only non negative coefficients limits
+    /// every variable in it to `rhs / coefficient`. Fails if a variable has no such equation.
+    pub fn upper_bounds(&self) -> Result<Vec<i64>, String> {
+        let mut bounds = vec![None; self.variables()];
+        for (row, &rhs) in self.equations.iter().zip(&self.rhs) {
+            if row.iter().any(|&c| c < 0) {
+                continue;
+            }
+            for (bound, &c) in bounds.iter_mut().zip(row) {
+                if c > 0 {
+                    let limit = rhs.max(0) / c;
+                    *bound = Some(bound.map_or(limit, |b: i64| b.min(limit)));
+                }
+            }
+        }
+        bounds
+            .into_iter()
+            .enumerate()
+            .map(|(var, bound)| bound.ok_or(format!("Variable {var} is unbounded")))
+            .collect()
+    }
+
+    /// An optimal assignment of the variables. Fails if there is none.
+    pub fn solve(&self) -> Result<Vec<u64>, String> {
+        let bounds = self.upper_bounds()?;
+        let echelon = Echelon::new(&self.equations, &self.rhs)?;
+        let mut search = Search::new(self, &echelon, &bounds);
+        search.run(0);
+
+        let free = search
+            .best
+            .ok_or("The equations have no non negative integer solution")?;
+        let mut values = vec![0; self.variables()];
+        for (&var, &value) in search.order.iter().zip(&free) {
+            values[var] = value as u64;
+        }
+        for pivot in &echelon.pivots {
+            let rest: i128 = pivot
+                .coefficients
+                .iter()
+                .map(|&(var, c)| c * values[var] as i128)
+                .sum();
+            values[pivot.var] = ((pivot.rhs - rest) / pivot.coefficient) as u64;
+        }
+        Ok(values)
+    }
+}
+
+/// `coefficient * var + Σ coefficients * free variable = rhs`
+struct Pivot {
+    var: usize,
+    coefficient: i128,
+    coefficients: Vec<(usize, i128)>,
+    rhs: i128,
+}
+
+/// The equations in reduced row echelon form, every pivot variable appears in only one row
+struct Echelon {
+    pivots: Vec<Pivot>,
+    free: Vec<usize>,
+}
+
+impl Echelon {
+    fn new(equations: &[Vec<i64>], rhs: &[i64]) -> Result<Self, String> {
+        let variables = equations.first().map_or(0, Vec::len);
+        let mut rows: Vec<Vec<i128>> = equations
+            .iter()
+            .zip(rhs)
+            .map(|(row, &rhs)| row.iter().chain([&rhs]).map(|&c| c as i128).collect())
+            .collect();
+
+        let mut pivot_vars = Vec::new();
+        let mut free = Vec::new();
+        for var in 0..variables {
+            let next = pivot_vars.len();
+            let Some(found) = (next..rows.len()).find(|&r| rows[r][var] != 0) else {
+                free.push(var);
+                continue;
+            };
+            rows.swap(next, found);
+            let pivot = rows[next].clone();
+            for (r, row) in rows.iter_mut().enumerate() {
+                if r == next || row[var] == 0 {
+                    continue;
+                }
+                // Fraction free: scale the row instead of dividing the pivot row
+                let factor = row[var];
+                for (c, &p) in row.iter_mut().zip(&pivot) {
+                    *c = *c * pivot[var] - p * factor;
+                }
+                normalize(row);
+            }
+            normalize(&mut rows[next]);
+            pivot_vars.push(var);
+        }
+
+        // The remaining rows are all zero on the left, so they have to be zero on the right
+        if rows[pivot_vars.len()..]
+            .iter()
+            .any(|row| row[variables] != 0)
+        {
+            return Err("The equations contradict each other".to_string());
+        }
+
+        let pivots = pivot_vars
+            .into_iter()
+            .zip(rows)
+            .map(|(var, mut row)| {
+                // Scaling the rows can flip the sign of earlier pivots
+                if row[var] < 0 {
+                    row.iter_mut().for_each(|c| *c = -*c);
+                }
+                (var, row)
+            })
+            .map(|(var, row)| Pivot {
+                var,
+                coefficient: row[var],
+                coefficients: free
+                    .iter()
+                    .filter(|&&f| row[f] != 0)
+                    .map(|&f| (f, row[f]))
+                    .collect(),
+                rhs: row[variables],
+            })
+            .collect();
+        Ok(Echelon { pivots, free })
+    }
+}
+
+/// Divides the row by the greatest common divisor of its entries, to keep the numbers small
+fn normalize(row: &mut [i128]) {
+    let divisor = row.iter().fold(0, |a, &b| gcd(a, b.abs()));
+    if divisor > 1 {
+        row.iter_mut().for_each(|c| *c /= divisor);
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Depth first search over the free variables
+struct Search<'a> {
+    echelon: &'a Echelon,
+    bounds: &'a [i64],
+    /// Free variables in the order they are assigned, smallest range first
+    order: Vec<usize>,
+    /// Coefficient of each free variable in `order`, per pivot
+    coefficients: Vec<Vec<i128>>,
+    /// What is left of each pivot's right hand side after the assigned free variables
+    residual: Vec<i128>,
+    /// Smallest and largest sum of the free variables from `order[depth..]`, per pivot and depth
+    remaining: Vec<Vec<(i128, i128)>>,
+    /// The cost, scaled so it stays an integer, is `base + Σ weights * free variable`
+    base: i128,
+    weights: Vec<i128>,
+    /// Smallest weighted sum of the free variables from `order[depth..]`
+    remaining_weight: Vec<i128>,
+    /// Scaled cost of the assigned free variables
+    partial: i128,
+    values: Vec<i64>,
+    best_cost: i128,
+    best: Option<Vec<i64>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(program: &IntegerProgram, echelon: &'a Echelon, bounds: &'a [i64]) -> Self {
+        let mut order = echelon.free.clone();
+        order.sort_by_key(|&var| bounds[var]);
+
+        let coefficients: Vec<Vec<i128>> = echelon
+            .pivots
+            .iter()
+            .map(|pivot| {
+                order
+                    .iter()
+                    .map(|&f| {
+                        pivot
+                            .coefficients
+                            .iter()
+                            .find(|&&(var, _)| var == f)
+                            .map_or(0, |&(_, c)| c)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let range = |c: i128, var: usize| {
+            let end = c * bounds[var] as i128;
+            (end.min(0), end.max(0))
+        };
+        let remaining = coefficients
+            .iter()
+            .map(|row| {
+                let mut sums = vec![(0, 0); order.len() + 1];
+                for depth in (0..order.len()).rev() {
+                    let (lo, hi) = range(row[depth], order[depth]);
+                    sums[depth] = (sums[depth + 1].0 + lo, sums[depth + 1].1 + hi);
+                }
+                sums
+            })
+            .collect();
+
+        // Substitute the pivot variables into the cost, scaled so everything stays an integer
+        let scale = echelon
+            .pivots
+            .iter()
+            .fold(1, |l, p| l / gcd(l, p.coefficient) * p.coefficient);
+        let cost = |var: usize| program.costs[var] as i128;
+        let base = echelon
+            .pivots
+            .iter()
+            .map(|p| cost(p.var) * p.rhs * (scale / p.coefficient))
+            .sum();
+        let weights: Vec<i128> = order
+            .iter()
+            .enumerate()
+            .map(|(i, &f)| {
+                let through_pivots: i128 = echelon
+                    .pivots
+                    .iter()
+                    .zip(&coefficients)
+                    .map(|(p, row)| cost(p.var) * row[i] * (scale / p.coefficient))
+                    .sum();
+                cost(f) * scale - through_pivots
+            })
+            .collect();
+        let mut remaining_weight = vec![0; order.len() + 1];
+        for depth in (0..order.len()).rev() {
+            remaining_weight[depth] =
+                remaining_weight[depth + 1] + range(weights[depth], order[depth]).0;
+        }
+
+        Search {
+            echelon,
+            bounds,
+            residual: echelon.pivots.iter().map(|p| p.rhs).collect(),
+            values: vec![0; order.len()],
+            order,
+            coefficients,
+            remaining,
+            base,
+            weights,
+            remaining_weight,
+            partial: 0,
+            best_cost: i128::MAX,
+            best: None,
+        }
+    }
+
+    /// Can every pivot variable still be between 0 and its bound?
+    fn feasible(&self, depth: usize) -> bool {
+        self.echelon.pivots.iter().enumerate().all(|(p, pivot)| {
+            let (lo, hi) = self.remaining[p][depth];
+            let max = pivot.coefficient * self.bounds[pivot.var] as i128;
+            self.residual[p] - hi <= max && self.residual[p] - lo >= 0
+        })
+    }
+
+    fn run(&mut self, depth: usize) {
+        let lower_bound = self.base + self.partial + self.remaining_weight[depth];
+        if lower_bound >= self.best_cost || !self.feasible(depth) {
+            return;
+        }
+        if depth == self.order.len() {
+            // Every pivot variable is in range, it also has to be an integer
+            let integral = self
+                .echelon
+                .pivots
+                .iter()
+                .zip(&self.residual)
+                .all(|(pivot, &residual)| residual % pivot.coefficient == 0);
+            if integral {
+                self.best_cost = lower_bound;
+                self.best = Some(self.values.clone());
+            }
+            return;
+        }
+
+        // Cheapest values first, so a good solution is found early and cuts the rest
+        let bound = self.bounds[self.order[depth]];
+        for step in 0..=bound {
+            let value = if self.weights[depth] < 0 {
+                bound - step
+            } else {
+                step
+            };
+            self.assign(depth, value as i128);
+            self.values[depth] = value;
+            // The following values only cost more
+            let too_expensive =
+                self.base + self.partial + self.remaining_weight[depth + 1] >= self.best_cost;
+            if !too_expensive {
+                self.run(depth + 1);
+            }
+            self.assign(depth, -(value as i128));
+            if too_expensive {
+                break;
+            }
+        }
+        self.values[depth] = 0;
+    }
+
+    /// Adds `delta` to the free variable at `depth`
+    fn assign(&mut self, depth: usize, delta: i128) {
+        for (residual, row) in self.residual.iter_mut().zip(&self.coefficients) {
+            *residual -= row[depth] * delta;
+        }
+        self.partial += self.weights[depth] * delta;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
+
+    /// Tries every assignment within the bounds
+    fn brute_force(program: &IntegerProgram) -> Option<u64> {
+        let bounds = program.upper_bounds().ok()?;
+        let mut values = vec![0u64; program.variables()];
+        let mut best = None;
+        loop {
+            let solves = program
+                .equations
+                .iter()
+                .zip(&program.rhs)
+                .all(|(row, &rhs)| {
+                    row.iter()
+                        .zip(&values)
+                        .map(|(&c, &v)| c * v as i64)
+                        .sum::<i64>()
+                        == rhs
+                });
+            if solves {
+                let cost = program.cost(&values);
+                best = Some(best.map_or(cost, |b: u64| b.min(cost)));
+            }
+            // Next assignment, like counting with a different base per digit
+            let Some(var) = (0..values.len()).find(|&var| values[var] < bounds[var] as u64) else {
+                return best;
+            };
+            values[var] += 1;
+            values[..var].iter_mut().for_each(|v| *v = 0);
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        // The first machine of the day 10 example
+        let program = IntegerProgram::new(
+            vec![
+                vec![0, 0, 0, 0, 1, 1],
+                vec![0, 1, 0, 0, 0, 1],
+                vec![0, 0, 1, 1, 1, 0],
+                vec![1, 1, 0, 1, 0, 0],
+            ],
+            vec![3, 5, 4, 7],
+            vec![1; 6],
+        )
+        .unwrap();
+        let values = program.solve().unwrap();
+        assert_eq!(program.cost(&values), 10);
+        assert_eq!(brute_force(&program), Some(10));
+    }
+
+    #[test]
+    fn test_rounding() {
+        // The relaxation has the optimum x = 1.5, y = 0.5 with cost 2, the integer optimum is 3
+        let program = IntegerProgram::new(
+            vec![vec![1, 1, 0], vec![1, 0, 2]],
+            vec![2, 3],
+            vec![1, 1, 1],
+        )
+        .unwrap();
+        let values = program.solve().unwrap();
+        assert_eq!(values, vec![1, 1, 1]);
+        assert_eq!(program.cost(&values), 3);
+    }
+
+    #[test]
+    fn test_infeasible() {
+        // Both buttons add 2, so an odd target can't be reached
+        let program = IntegerProgram::new(vec![vec![2, 2]], vec![3], vec![1, 1]).unwrap();
+        assert!(program.solve().is_err());
+        // Contradicting equations
+        let program =
+            IntegerProgram::new(vec![vec![1, 1], vec![1, 1]], vec![1, 2], vec![1, 1]).unwrap();
+        assert!(program.solve().is_err());
+        // Unbounded variable
+        let program = IntegerProgram::new(vec![vec![1, -1]], vec![0], vec![1, 1]).unwrap();
+        assert!(program.solve().is_err());
+        assert!(IntegerProgram::new(vec![vec![1]], vec![1], vec![-1]).is_err());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_matches_brute_force(
+            equations in prop::collection::vec(prop::collection::vec(0i64..3, 4), 1..4),
+            rhs in prop::collection::vec(0i64..8, 4),
+            costs in prop::collection::vec(0i64..4, 4),
+        ) {
+            let rhs = rhs[..equations.len()].to_vec();
+            let program = IntegerProgram::new(equations, rhs, costs).unwrap();
+            let expected = brute_force(&program);
+            match program.solve() {
+                Ok(values) => {
+                    for (row, &rhs) in program.equations.iter().zip(&program.rhs) {
+                        prop_assert_eq!(row.iter().zip(&values).map(|(&c, &v)| c * v as i64).sum::<i64>(), rhs);
+                    }
+                    prop_assert_eq!(Some(program.cost(&values)), expected);
+                }
+                Err(_) => prop_assert!(expected.is_none()),
+            }
+        }
+    }
+}
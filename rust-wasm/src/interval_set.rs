@@ -0,0 +1,221 @@
+//! A set of values stored as sorted, disjoint half open ranges, like the ID ranges of days 2 and 5.
+
+use std::iter::Sum;
+use std::ops::{Range, Sub};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    /// Sorted, non empty and never overlapping or touching, so `a.end < b.start` for neighbours
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+}
+
+impl<T: Ord + Copy> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds all values of the range, merging it with the ranges it overlaps or touches
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // Ranges in first..last overlap or touch the new range
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = match self.ranges[first..last] {
+            [] => range,
+            [ref a, .., ref b] | [ref a @ ref b] => a.start.min(range.start)..b.end.max(range.end),
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// The merged ranges in ascending order
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// O(log n) in the number of ranges
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    /// Checks many values at once. The values are sorted first, so the ranges are only walked once.
+    pub fn contains_all(&self, values: &[T]) -> Vec<bool> {
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_unstable_by_key(|&i| values[i]);
+
+        let mut contained = vec![false; values.len()];
+        let mut ranges = self.ranges.iter().peekable();
+        for i in order {
+            while ranges.next_if(|r| r.end <= values[i]).is_some() {}
+            contained[i] = ranges.peek().is_some_and(|r| r.start <= values[i]);
+        }
+        contained
+    }
+
+    /// All values that are in either set
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    /// All values that are in both sets
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        let mut ranges = Vec::new();
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let overlap = x.start.max(y.start)..x.end.min(y.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            // Drop the range that ends first, it can't overlap anything else
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// All values of `self` that are not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut others = other.ranges.iter().peekable();
+        for range in &self.ranges {
+            let mut start = range.start;
+            while others.next_if(|o| o.end <= start).is_some() {}
+            // Cut out every range of `other` that overlaps, the last one might overlap the next range too
+            for cut in others.clone().take_while(|o| o.start < range.end) {
+                if start < cut.start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        IntervalSet { ranges }
+    }
+}
+
+impl<T: Ord + Copy + Sub<Output = T> + Sum> IntervalSet<T> {
+    /// Number of values in the set
+    pub fn total_len(&self) -> T {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+}
+
+impl<T: Ord + Copy> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut ranges: Vec<Range<T>> = iter.into_iter().filter(|r| !r.is_empty()).collect();
+        ranges.sort_unstable_by_key(|r| r.start);
+
+        // Merging sorted ranges only ever touches the last one
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        IntervalSet { ranges: merged }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_insert() {
+        let mut set = IntervalSet::new();
+        set.insert(10..15);
+        set.insert(1..3);
+        set.insert(20..25);
+        set.insert(5..5);
+        assert_eq!(set.ranges(), &[1..3, 10..15, 20..25]);
+
+        // Touching ranges are merged, so are all ranges a new range covers
+        set.insert(3..4);
+        set.insert(12..21);
+        assert_eq!(set.ranges(), &[1..4, 10..25]);
+        assert_eq!(set.total_len(), 18u64);
+        assert_eq!(
+            set,
+            [12..21, 1..3, 20..25, 3..4, 10..15].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_contains() {
+        let set: IntervalSet<u64> = [3..6, 10..15, 16..21].into_iter().collect();
+        let values = [1, 5, 8, 3, 17, 11, 16, 15, 20, 21, 6];
+        let expected = values.map(|v| set.ranges().iter().any(|r| r.contains(&v)));
+        assert_eq!(values.map(|v| set.contains(v)), expected);
+        assert_eq!(set.contains_all(&values), expected);
+        assert!(!IntervalSet::new().contains(0));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet<u64> = [0..10, 20..30].into_iter().collect();
+        let b: IntervalSet<u64> = [5..22, 25..26, 28..40].into_iter().collect();
+        assert_eq!(a.union(&b).ranges(), std::slice::from_ref(&(0..40)));
+        assert_eq!(
+            a.intersection(&b).ranges(),
+            &[5..10, 20..22, 25..26, 28..30]
+        );
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 22..25, 26..28]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20, 30..40]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_matches_hash_set(
+            a in prop::collection::vec((0u64..100, 0u64..20), 0..8),
+            b in prop::collection::vec((0u64..100, 0u64..20), 0..8),
+        ) {
+            use std::collections::HashSet;
+
+            let to_set = |ranges: &[(u64, u64)]| ranges.iter().map(|&(s, w)| s..s + w).collect::<IntervalSet<u64>>();
+            let to_hash = |ranges: &[(u64, u64)]| ranges.iter().flat_map(|&(s, w)| s..s + w).collect::<HashSet<u64>>();
+            let (set_a, set_b) = (to_set(&a), to_set(&b));
+            let (hash_a, hash_b) = (to_hash(&a), to_hash(&b));
+
+            let values = |set: &IntervalSet<u64>| set.ranges().iter().flat_map(|r| r.clone()).collect::<HashSet<u64>>();
+            prop_assert_eq!(set_a.total_len(), hash_a.len() as u64);
+            prop_assert_eq!(values(&set_a.union(&set_b)), &hash_a | &hash_b);
+            prop_assert_eq!(values(&set_a.intersection(&set_b)), &hash_a & &hash_b);
+            prop_assert_eq!(values(&set_a.difference(&set_b)), &hash_a - &hash_b);
+
+            // Building the set one range at a time gives the same ranges as collecting
+            let mut inserted = IntervalSet::new();
+            for &(s, w) in &a {
+                inserted.insert(s..s + w);
+            }
+            prop_assert_eq!(&inserted, &set_a);
+        }
+    }
+}
@@ -0,0 +1,374 @@
+//! A k-d tree for nearest neighbour queries, and a lazy stream of all point pairs ordered by distance.
+//! Points can be labelled, to find the closest point with another label, like another component of
+//! a spanning forest.
+//!
+//! Distances are squared and exact, so there are no float rounding issues when comparing them.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+use crate::point::Point;
+
+/// A pair of points `a < b`, given by their indices. Ordered by distance, then `a`, then `b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Edge {
+    pub distance_squared: u128,
+    pub a: usize,
+    pub b: usize,
+}
+
+/// Subtrees with at most this many points are searched by checking every point
+const LEAF_SIZE: usize = 8;
+
+/// A balanced k-d tree over the points, built once and never changed
+pub struct KdTree<'a, const N: usize> {
+    points: &'a [Point<N>],
+    /// Point indices. The median of every slice is a node, its halves are the subtrees.
+    nodes: Vec<usize>,
+    /// The points in the order of `nodes`, so a subtree is close together in memory
+    sorted: Vec<Point<N>>,
+}
+
+impl<'a, const N: usize> KdTree<'a, N> {
+    pub fn new(points: &'a [Point<N>]) -> Self {
+        let mut nodes: Vec<usize> = (0..points.len()).collect();
+        build(points, &mut nodes, 0);
+        let sorted = nodes.iter().map(|&i| points[i]).collect();
+        KdTree {
+            points,
+            nodes,
+            sorted,
+        }
+    }
+
+    pub fn points(&self) -> &'a [Point<N>] {
+        self.points
+    }
+
+    /// The `k` closest points to the point at `index`, without itself. Sorted by distance, ties by index.
+    pub fn nearest(&self, index: usize, k: usize) -> Vec<(u128, usize)> {
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(0..self.nodes.len(), 0, index, k, &mut best);
+        }
+        best.into_sorted_vec()
+    }
+
+    /// Searches the subtree of the nodes in `range`
+    fn search(
+        &self,
+        range: Range<usize>,
+        depth: usize,
+        index: usize,
+        k: usize,
+        best: &mut BinaryHeap<(u128, usize)>,
+    ) {
+        let target = &self.points[index];
+        let mut offer = |node: usize| {
+            let other = self.nodes[node];
+            if other == index {
+                return;
+            }
+            let distance = self.sorted[node].distance_squared(target);
+            if best.len() < k {
+                best.push((distance, other));
+            } else if best.peek().is_some_and(|&worst| (distance, other) < worst) {
+                best.pop();
+                best.push((distance, other));
+            }
+        };
+        if range.len() <= LEAF_SIZE {
+            range.for_each(offer);
+            return;
+        }
+        let mid = (range.start + range.end) / 2;
+        offer(mid);
+
+        let axis = depth % N;
+        let split = self.sorted[mid][axis];
+        let (near, far) = if target[axis] < split {
+            (range.start..mid, mid + 1..range.end)
+        } else {
+            (mid + 1..range.end, range.start..mid)
+        };
+        self.search(near, depth + 1, index, k, best);
+        // Points on the other side are at least as far away as the splitting plane.
+        // Equal distances still have to be searched, they might have a smaller index.
+        let plane = u128::from(target[axis].abs_diff(split)).pow(2);
+        if best.len() < k || best.peek().is_some_and(|&(worst, _)| plane <= worst) {
+            self.search(far, depth + 1, index, k, best);
+        }
+    }
+
+    /// Labels every point, for example with its component. Subtrees whose points all have the
+    /// same label are marked, so [`KdTree::nearest_other`] can skip them at once.
+    pub fn labels(&self, points: Vec<usize>) -> Labels {
+        assert_eq!(points.len(), self.points.len(), "Every point needs a label");
+        let mut labels = Labels {
+            subtrees: vec![None; points.len()],
+            points,
+        };
+        if !self.nodes.is_empty() {
+            self.label_subtree(0..self.nodes.len(), &mut labels);
+        }
+        labels
+    }
+
+    /// Marks the subtree of the nodes in `range`, which can't be empty. Returns its label, `None`
+    /// if the points have different ones.
+    fn label_subtree(&self, range: Range<usize>, labels: &mut Labels) -> Option<usize> {
+        let label_of = |labels: &Labels, node: usize| labels.points[self.nodes[node]];
+        let first = label_of(labels, range.start);
+        let label = if range.len() <= LEAF_SIZE {
+            range
+                .clone()
+                .all(|node| label_of(labels, node) == first)
+                .then_some(first)
+        } else {
+            let mid = (range.start + range.end) / 2;
+            let own = label_of(labels, mid);
+            let left = self.label_subtree(range.start..mid, labels);
+            let right = self.label_subtree(mid + 1..range.end, labels);
+            (left == Some(own) && right == Some(own)).then_some(own)
+        };
+        labels.subtrees[subtree_key(&range)] = label;
+        label
+    }
+
+    /// Replaces `best` with the edge from the point at `index` to the closest point with a
+    /// different label, if that edge is shorter
+    pub fn nearest_other(&self, index: usize, labels: &Labels, best: &mut Option<Edge>) {
+        if !self.nodes.is_empty() {
+            self.search_other(0..self.nodes.len(), 0, index, labels, best);
+        }
+    }
+
+    fn search_other(
+        &self,
+        range: Range<usize>,
+        depth: usize,
+        index: usize,
+        labels: &Labels,
+        best: &mut Option<Edge>,
+    ) {
+        let label = labels.points[index];
+        if range.is_empty() || labels.subtrees[subtree_key(&range)] == Some(label) {
+            return;
+        }
+        let target = &self.points[index];
+        let mut offer = |node: usize| {
+            let other = self.nodes[node];
+            if labels.points[other] == label {
+                return;
+            }
+            let edge = Edge {
+                distance_squared: self.sorted[node].distance_squared(target),
+                a: index.min(other),
+                b: index.max(other),
+            };
+            if best.is_none_or(|best| edge < best) {
+                *best = Some(edge);
+            }
+        };
+        if range.len() <= LEAF_SIZE {
+            range.for_each(offer);
+            return;
+        }
+        let mid = (range.start + range.end) / 2;
+        offer(mid);
+
+        let axis = depth % N;
+        let split = self.sorted[mid][axis];
+        let (near, far) = if target[axis] < split {
+            (range.start..mid, mid + 1..range.end)
+        } else {
+            (mid + 1..range.end, range.start..mid)
+        };
+        self.search_other(near, depth + 1, index, labels, best);
+        // Like in `search`, equal distances might still have a smaller index
+        let plane = u128::from(target[axis].abs_diff(split)).pow(2);
+        if best.is_none_or(|best| plane <= best.distance_squared) {
+            self.search_other(far, depth + 1, index, labels, best);
+        }
+    }
+
+    /// Every pair of points, ordered like [`Edge`]. The pairs are found as they are needed,
+    /// so taking only the shortest ones is cheap. Taking all of them is quadratic.
+    pub fn pairs_by_distance(&self) -> PairsByDistance<'_, 'a, N> {
+        let mut pairs = PairsByDistance {
+            tree: self,
+            neighbours: vec![Vec::new(); self.points.len()],
+            next: vec![0; self.points.len()],
+            queue: BinaryHeap::with_capacity(self.points.len()),
+        };
+        for index in 0..self.points.len() {
+            pairs.advance(index);
+        }
+        pairs
+    }
+}
+
+/// See [`KdTree::labels`]
+pub struct Labels {
+    /// Label of each point, by point index
+    points: Vec<usize>,
+    /// Label of each subtree if all of its points share it, by [`subtree_key`]
+    subtrees: Vec<Option<usize>>,
+}
+
+/// A leaf is stored at its first node and every other subtree at its middle node. The first
+/// node of a leaf is never the middle of another subtree, so the keys are unique.
+fn subtree_key(range: &Range<usize>) -> usize {
+    if range.len() <= LEAF_SIZE {
+        range.start
+    } else {
+        (range.start + range.end) / 2
+    }
+}
+
+/// Sorts the points into a k-d tree, splitting at the median of each axis in turn
+fn build<const N: usize>(points: &[Point<N>], nodes: &mut [usize], depth: usize) {
+    if nodes.len() <= LEAF_SIZE {
+        return;
+    }
+    let mid = nodes.len() / 2;
+    let axis = depth % N;
+    nodes.select_nth_unstable_by_key(mid, |&i| points[i][axis]);
+    let (left, right) = nodes.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+/// See [`KdTree::pairs_by_distance`]
+pub struct PairsByDistance<'t, 'a, const N: usize> {
+    tree: &'t KdTree<'a, N>,
+    /// The closest neighbours of each point found so far, more are queried when they run out
+    neighbours: Vec<Vec<(u128, usize)>>,
+    /// Index into `neighbours` of the next pair for each point
+    next: Vec<usize>,
+    /// The next pair of each point, every point has at most one pair queued
+    queue: BinaryHeap<Reverse<Edge>>,
+}
+
+impl<const N: usize> PairsByDistance<'_, '_, N> {
+    /// Queues the next pair of the point. Each pair is only produced by its lower index.
+    fn advance(&mut self, index: usize) {
+        loop {
+            let neighbours = &mut self.neighbours[index];
+            if self.next[index] == neighbours.len() {
+                let found = neighbours.len();
+                if found + 1 >= self.tree.points.len() {
+                    // Every other point was already paired, the memory can go
+                    *neighbours = Vec::new();
+                    return;
+                }
+                // Double the neighbours, the closest ones stay the same
+                *neighbours = self.tree.nearest(index, (found * 2).max(16));
+            }
+            let (distance, other) = self.neighbours[index][self.next[index]];
+            self.next[index] += 1;
+            if index < other {
+                self.queue.push(Reverse(Edge {
+                    distance_squared: distance,
+                    a: index,
+                    b: other,
+                }));
+                return;
+            }
+        }
+    }
+}
+
+impl<const N: usize> Iterator for PairsByDistance<'_, '_, N> {
+    type Item = Edge;
+
+    fn next(&mut self) -> Option<Edge> {
+        let Reverse(edge) = self.queue.pop()?;
+        self.advance(edge.a);
+        Some(edge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
+
+    /// All pairs, sorted the way the tree should yield them
+    fn all_pairs<const N: usize>(points: &[Point<N>]) -> Vec<Edge> {
+        let mut pairs = Vec::new();
+        for a in 0..points.len() {
+            for b in a + 1..points.len() {
+                let distance_squared = points[a].distance_squared(&points[b]);
+                pairs.push(Edge {
+                    distance_squared,
+                    a,
+                    b,
+                });
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    #[test]
+    fn test_nearest() {
+        let points = [[0, 0], [5, 5], [1, 0], [0, 2], [-3, 0], [1, 0]].map(Point);
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.nearest(0, 3), vec![(1, 2), (1, 5), (4, 3)]);
+        // The duplicate position counts as a neighbour
+        assert_eq!(tree.nearest(2, 1), vec![(0, 5)]);
+        assert_eq!(tree.nearest(1, 10).len(), 5);
+    }
+
+    #[test]
+    fn test_nearest_other() {
+        let points = [[0, 0], [1, 0], [5, 0], [2, 0], [9, 0], [3, 0]].map(Point);
+        let tree = KdTree::new(&points);
+        let labels = tree.labels(vec![0, 0, 1, 0, 1, 0]);
+        let mut best = None;
+        tree.nearest_other(0, &labels, &mut best);
+        assert_eq!(best.map(|e| (e.a, e.b)), Some((0, 2)));
+        // A longer edge doesn't replace a shorter one
+        tree.nearest_other(4, &labels, &mut best);
+        tree.nearest_other(5, &labels, &mut best);
+        assert_eq!(best.map(|e| (e.a, e.b)), Some((2, 5)));
+
+        // Everything has the same label, so there is nothing to find
+        let labels = tree.labels(vec![7; 6]);
+        let mut best = None;
+        tree.nearest_other(3, &labels, &mut best);
+        assert_eq!(best, None);
+    }
+
+    #[test]
+    fn test_pairs_by_distance() {
+        let points = [
+            [3, 1, 4],
+            [1, 5, 9],
+            [2, 6, 5],
+            [3, 5, 8],
+            [9, 7, 9],
+            [3, 2, 3],
+        ]
+        .map(Point);
+        let tree = KdTree::new(&points);
+        assert_eq!(
+            tree.pairs_by_distance().collect::<Vec<_>>(),
+            all_pairs(&points)
+        );
+        assert_eq!(KdTree::<2>::new(&[]).pairs_by_distance().next(), None);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_pairs_match_sorting(points in prop::collection::vec(prop::array::uniform3(-20i64..20), 0..60)) {
+            let points: Vec<Point<3>> = points.into_iter().map(Point).collect();
+            let tree = KdTree::new(&points);
+            prop_assert_eq!(tree.pairs_by_distance().collect::<Vec<_>>(), all_pairs(&points));
+        }
+    }
+}
@@ -1,4 +1,27 @@
+pub mod alloc_profile;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bench_compare;
+pub mod dancing_links;
+pub mod disjoint_set;
 pub mod download_input;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fuzzing;
+pub mod generate;
+pub mod geometry;
+pub mod gf2;
+pub mod graph_export;
+pub mod grid;
+pub mod ilp;
+pub mod interval_set;
+pub mod kd_tree;
+pub mod mst;
+pub mod parsing;
+pub mod path_count;
+pub mod point;
+pub mod polygon;
+pub mod polyomino;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod readme;
 
 use wasm_bindgen::prelude::*;
 
@@ -49,6 +72,8 @@ pub struct Day {
     #[wasm_bindgen(skip)]
     pub example_fn: fn() -> String,
     #[wasm_bindgen(skip)]
+    pub parse: Option<fn(&str)>,
+    #[wasm_bindgen(skip)]
     pub part1: Option<fn(&str) -> String>,
     #[wasm_bindgen(skip)]
     pub part2: Option<fn(&str) -> String>,
@@ -101,20 +126,132 @@ pub fn get_days() -> Vec<Day> {
     DAYS.iter().map(|&&d| d).collect()
 }
 
+/// Draws the input of a day as an SVG image. Only days with a picture worth seeing have one.
+#[wasm_bindgen]
+pub fn render(day: u32, input: &str) -> Result<String, String> {
+    match day {
+        9 => day_09::render_svg(input),
+        _ => Err(format!("No renderer for day {day}")),
+    }
+}
+
+/// Exports the graph of a day as `dot`, `graphml` or `json`, with the number of paths from `from`
+/// to every node. `highlight` is a comma separated list of `node=colour`.
+#[wasm_bindgen]
+pub fn export_graph(
+    day: u32,
+    input: &str,
+    format: &str,
+    from: &str,
+    highlight: &str,
+) -> Result<String, String> {
+    match day {
+        11 => day_11::export_graph(input, format, from, highlight),
+        _ => Err(format!("No graph for day {day}")),
+    }
+}
+
+/// Part 2 of day 10 with the solver called `solver`, or `all` to compare every solver
+#[wasm_bindgen]
+pub fn day_10_solver(solver: &str, input: &str) -> String {
+    __answer(day_10::solve_b_using(input, solver))
+}
+
+/// Solves every machine of day 10 on its own and shows the pressed buttons, checked by
+/// simulating the machine
+#[wasm_bindgen]
+pub fn day_10_explain(solver: &str, input: &str) -> String {
+    __answer(day_10::explain_using(input, solver))
+}
+
+/// Names of the day 10 solvers that are compiled in
+#[wasm_bindgen]
+pub fn day_10_solvers() -> Vec<String> {
+    day_10::solvers()
+        .iter()
+        .map(|solver| solver.name().to_string())
+        .collect()
+}
+
 #[doc(hidden)]
 pub fn __to_string<T: std::fmt::Display>(value: T) -> String {
     value.to_string()
 }
 
+/// The result of a part. Parts that can fail on bad input return a `Result`, the error is then
+/// shown instead of the answer.
+pub trait Answer {
+    fn into_answer(self) -> String;
+}
+
+macro_rules! display_answer {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl Answer for $t {
+                fn into_answer(self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+display_answer!(u32, u64, u128, usize, i64, String);
+
+impl<T: Answer, E: std::fmt::Display> Answer for Result<T, E> {
+    fn into_answer(self) -> String {
+        match self {
+            Ok(answer) => answer.into_answer(),
+            Err(e) => format!("Error: {e}"),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn __answer<T: Answer>(value: T) -> String {
+    value.into_answer()
+}
+
+/// Registers a day. The parse function is optional and only used to measure the parsing cost
+/// separately in the benchmarks, it has to be the last argument: `parse = parse`
 #[macro_export]
 macro_rules! solution {
+    ($day:expr, $title:expr, $description:expr, $example:expr, $part1:expr, parse = $parse:expr) => {
+        pub static SOLUTION: $crate::Day = $crate::Day {
+            day: $day,
+            title_fn: || -> String { $crate::__to_string($title) },
+            description: || -> String { $crate::__to_string($description) },
+            example_fn: || -> String { $crate::__to_string($example) },
+            parse: Some(|input| {
+                let _ = std::hint::black_box($parse(input));
+            }),
+            part1: Some(|input| -> String { $crate::__answer($part1(input)) }),
+            part2: None,
+        };
+    };
+
+    ($day:expr, $title:expr, $description:expr, $example:expr, $part1:expr, $part2:expr, parse = $parse:expr) => {
+        pub static SOLUTION: $crate::Day = $crate::Day {
+            day: $day,
+            title_fn: || -> String { $crate::__to_string($title) },
+            description: || -> String { $crate::__to_string($description) },
+            example_fn: || -> String { $crate::__to_string($example) },
+            parse: Some(|input| {
+                let _ = std::hint::black_box($parse(input));
+            }),
+            part1: Some(|input| -> String { $crate::__answer($part1(input)) }),
+            part2: Some(|input| -> String { $crate::__answer($part2(input)) }),
+        };
+    };
+
     ($day:expr, $title:expr, $description:expr, $example:expr, $part1:expr) => {
         pub static SOLUTION: $crate::Day = $crate::Day {
             day: $day,
             title_fn: || -> String { $crate::__to_string($title) },
             description: || -> String { $crate::__to_string($description) },
             example_fn: || -> String { $crate::__to_string($example) },
-            part1: Some(|input| -> String { $crate::__to_string($part1(input)) }),
+            parse: None,
+            part1: Some(|input| -> String { $crate::__answer($part1(input)) }),
             part2: None,
         };
     };
@@ -125,8 +262,9 @@ macro_rules! solution {
             title_fn: || -> String { $crate::__to_string($title) },
             description: || -> String { $crate::__to_string($description) },
             example_fn: || -> String { $crate::__to_string($example) },
-            part1: Some(|input| -> String { $crate::__to_string($part1(input)) }),
-            part2: Some(|input| -> String { $crate::__to_string($part2(input)) }),
+            parse: None,
+            part1: Some(|input| -> String { $crate::__answer($part1(input)) }),
+            part2: Some(|input| -> String { $crate::__answer($part2(input)) }),
         };
     };
 }
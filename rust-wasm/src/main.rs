@@ -3,17 +3,40 @@ use advent_of_code_2025::{DAYS, Day};
 #[cfg(not(target_arch = "wasm32"))]
 mod download_input;
 
+#[cfg(feature = "alloc_profile")]
+#[global_allocator]
+static GLOBAL: advent_of_code_2025::alloc_profile::CountingAllocator =
+    advent_of_code_2025::alloc_profile::CountingAllocator;
+
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("readme") => return readme(&args[1..]),
+        Some("bench-compare") => return bench_compare(&args[1..]),
+        Some("generate") => return generate(&args[1..]),
+        Some("render") => return render(&args[1..]),
+        Some("export") => return export(&args[1..]),
+        _ => {}
+    }
+
     println!("Running Advent of Code 2025...\n");
     println!("  Day  |  Part  | Solution");
     println!("-------+--------+------------------");
 
-    if let Some(day) = std::env::args().nth(1)
-        && let Some(day) = parse_day(&day)
+    if let Some(day) = args.first()
+        && let Some(day) = parse_day(day)
     {
-        if let Some(day) = DAYS.iter().find(|d| d.day == day) {
-            run_day(day);
+        if args.iter().any(|arg| arg == "--explain") {
+            explain(
+                day,
+                flag_value(&args, "--solver"),
+                flag_value(&args, "--input"),
+            );
+        } else if let Some(solver) = flag_value(&args, "--solver") {
+            run_solver(day, solver, flag_value(&args, "--input"));
+        } else if let Some(day) = DAYS.iter().find(|d| d.day == day) {
+            run_day(day, flag_value(&args, "--input"));
         } else {
             println!("Day {} not found", day);
         }
@@ -30,39 +53,343 @@ fn _for_types(_: Day) {
     _ = DAYS;
 }
 
+/// Runs both parts of a day, on the puzzle input or on the file at `input_path`
+#[cfg(not(target_arch = "wasm32"))]
+fn run_day(day: &Day, input_path: Option<&str>) {
+    let Some(input) = read_day_input(day.day, input_path) else {
+        return;
+    };
+
+    if let Some(p1) = day.part1 {
+        run_part(day.day, 1, || p1(&input));
+    }
+    if let Some(p2) = day.part2 {
+        run_part(day.day, 2, || p2(&input));
+    }
+}
+
+/// Runs part 2 of day 10 with a chosen solver, `all` compares every solver.
+/// Usage: `day10 --solver <exact|microlp|z3|all> [--input <path>]`
+#[cfg(not(target_arch = "wasm32"))]
+fn run_solver(day: u32, solver: &str, input_path: Option<&str>) {
+    if day != 10 {
+        println!("Day {day:2} | Only day 10 has different solvers");
+        return;
+    }
+    let Some(input) = read_day_input(day, input_path) else {
+        return;
+    };
+    run_part(day, 2, || {
+        advent_of_code_2025::day_10::solve_b_using(&input, solver)
+            .unwrap_or_else(|e| format!("Error: {e}"))
+    });
+}
+
+/// Shows how every machine of day 10 was solved, and if the presses really produce the lights and
+/// joltages. Usage: `day10 --explain [--solver <exact|microlp|z3|all>] [--input <path>]`
+#[cfg(not(target_arch = "wasm32"))]
+fn explain(day: u32, solver: Option<&str>, input_path: Option<&str>) {
+    use advent_of_code_2025::day_10;
+
+    if day != 10 {
+        println!("Day {day:2} | Only day 10 can be explained");
+        return;
+    }
+    let Some(input) = read_day_input(day, input_path) else {
+        return;
+    };
+    let explanation = match solver {
+        Some(solver) => day_10::explain_using(&input, solver),
+        None => day_10::explain(&input, day_10::default_solver()),
+    };
+    match explanation {
+        Ok(explanation) => println!("{explanation}"),
+        Err(e) => println!("Day {day:2} | Error: {e}"),
+    }
+}
+
+/// The puzzle input, downloaded if it is missing, or the file at `input_path`
 #[cfg(not(target_arch = "wasm32"))]
-fn run_day(day: &Day) {
-    if !download_input::check_if_present(day.day) {
-        println!("Day {:2} | Downloading Input ...", day.day);
-        if let Err(e) = download_input::download_input(day.day) {
-            let err = format!("Day {:2} | Failed to download input", day.day);
+fn read_day_input(day: u32, input_path: Option<&str>) -> Option<String> {
+    if input_path.is_none() && !download_input::check_if_present(day) {
+        println!("Day {:2} | Downloading Input ...", day);
+        if let Err(e) = download_input::download_input(day) {
+            let err = format!("Day {:2} | Failed to download input", day);
             println!("{err}: {e}");
+            return None;
+        }
+    }
+
+    let input = match input_path {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| e.to_string()),
+        None => download_input::read_input(day),
+    };
+    match input {
+        Ok(input) => Some(input),
+        Err(e) => {
+            println!("Day {:2} | Failed to read input: {}", day, e);
+            None
+        }
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "alloc_profile")))]
+fn run_part(day: u32, part: u32, solve: impl FnOnce() -> String) {
+    println!("Day {:2} | Part {} | {}", day, part, solve());
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "alloc_profile"))]
+fn run_part(day: u32, part: u32, solve: impl FnOnce() -> String) {
+    let (solution, stats) = advent_of_code_2025::alloc_profile::measure(solve);
+    println!("Day {:2} | Part {} | {}", day, part, solution);
+    println!("       |        | {stats}");
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_all() {
+    for day in DAYS {
+        run_day(day, None);
+        println!("-------+--------+------------------");
+    }
+}
+
+/// Rewrites the benchmark table in the README from the criterion results.
+/// Usage: `readme [--wasm <wasm bench output>] [--readme <path>]`
+#[cfg(not(target_arch = "wasm32"))]
+fn readme(args: &[String]) {
+    use advent_of_code_2025::readme;
+    use std::path::Path;
+
+    let wasm_output = flag_value(args, "--wasm");
+    let readme_path = flag_value(args, "--readme").unwrap_or("../README.md");
+
+    let native = match readme::read_criterion(Path::new("./target/criterion")) {
+        Ok(native) => native,
+        Err(e) => {
+            println!("{e}");
             return;
         }
+    };
+    let wasm = match wasm_output.map(std::fs::read_to_string) {
+        Some(Ok(output)) => readme::parse_wasm_output(&output),
+        Some(Err(e)) => {
+            println!("Failed to read wasm benchmark output: {e}");
+            return;
+        }
+        None => readme::Timings::new(),
+    };
+
+    let table = readme::benchmark_table(DAYS, &native, &wasm);
+    match readme::update_readme(Path::new(readme_path), &table) {
+        Ok(()) => println!("Updated benchmark table in '{readme_path}'"),
+        Err(e) => println!("{e}"),
+    }
+}
+
+/// Saves and compares criterion results against named baselines.
+/// Usage:
+/// - `bench-compare save [name]` stores the last `cargo bench` run, the name defaults to the current commit
+/// - `bench-compare check <name> [--threshold <percent>]` fails if anything got slower than the threshold
+/// - `bench-compare list` shows all saved baselines
+#[cfg(not(target_arch = "wasm32"))]
+fn bench_compare(args: &[String]) {
+    use advent_of_code_2025::bench_compare::{self, BaselineFile};
+    use std::path::Path;
+
+    const BASELINES: &str = "./bench_baselines.json";
+    let fail = |e: String| -> ! {
+        println!("{e}");
+        std::process::exit(1);
+    };
+
+    let mut file = BaselineFile::load(Path::new(BASELINES)).unwrap_or_else(|e| fail(e));
+    let current = || {
+        bench_compare::read_criterion(Path::new("./target/criterion")).unwrap_or_else(|e| fail(e))
+    };
+
+    match args.first().map(String::as_str) {
+        Some("save") => {
+            let name = match args.get(1) {
+                Some(name) => name.clone(),
+                None => current_commit().unwrap_or_else(|e| fail(e)),
+            };
+            file.baselines.insert(name.clone(), current());
+            file.save(Path::new(BASELINES)).unwrap_or_else(|e| fail(e));
+            println!("Saved baseline '{name}' to '{BASELINES}'");
+        }
+        Some("check") => {
+            let Some(name) = args.get(1) else {
+                fail("Usage: bench-compare check <name> [--threshold <percent>]".to_string());
+            };
+            let threshold = match flag_value(args, "--threshold").map(str::parse::<f64>) {
+                Some(Ok(threshold)) => threshold,
+                Some(Err(e)) => fail(format!("Invalid threshold: {e}")),
+                None => 10.0,
+            };
+            let Some(baseline) = file.baselines.get(name) else {
+                fail(format!("Baseline '{name}' not found in '{BASELINES}'"));
+            };
+
+            println!("  Day  |   Part   |    Baseline   |    Current    |  Change");
+            println!("-------+----------+---------------+---------------+----------");
+            let (comparisons, missing) = bench_compare::compare(baseline, &current());
+            let mut regressions = 0;
+            for comparison in &comparisons {
+                if comparison.is_regression(threshold) {
+                    regressions += 1;
+                    println!("{comparison} <- regression");
+                } else {
+                    println!("{comparison}");
+                }
+            }
+            for missing in &missing {
+                println!("{missing}");
+            }
+
+            if regressions > 0 {
+                fail(format!(
+                    "\n{regressions} benchmarks regressed by more than {threshold}% against '{name}'"
+                ));
+            }
+            if !missing.is_empty() {
+                fail(format!(
+                    "\n{} benchmarks of '{name}' are missing from the current run",
+                    missing.len()
+                ));
+            }
+            println!("\nNo regressions above {threshold}% against '{name}'");
+        }
+        Some("list") => {
+            for (name, measurements) in &file.baselines {
+                println!("{name} ({} days)", measurements.len());
+            }
+        }
+        _ => fail(
+            "Usage: bench-compare <save|check|list> [name] [--threshold <percent>]".to_string(),
+        ),
     }
+}
 
-    let input = match download_input::read_input(day.day) {
-        Ok(input) => input,
+/// Writes a generated input, to test how the solutions scale.
+/// Usage: `generate <day> [--scale <factor>] [--seed <seed>] [--output <path>]`
+#[cfg(not(target_arch = "wasm32"))]
+fn generate(args: &[String]) {
+    use advent_of_code_2025::generate;
+
+    let Some(day) = args.first().and_then(|day| parse_day(day)) else {
+        println!("Usage: generate <day> [--scale <factor>] [--seed <seed>] [--output <path>]");
+        return;
+    };
+    let scale = flag_value(args, "--scale").map_or(Ok(1), str::parse::<usize>);
+    let seed = flag_value(args, "--seed").map_or(Ok(0), str::parse::<u64>);
+    let (Ok(scale @ 1..), Ok(seed)) = (scale, seed) else {
+        println!("The scale has to be a positive number and the seed a non-negative one");
+        return;
+    };
+
+    let Some(input) = generate::generate(day, seed, scale) else {
+        println!("No generator for day {day}");
+        return;
+    };
+
+    let path = match flag_value(args, "--output") {
+        Some(path) => path.to_string(),
+        None => format!("./inputs/generated/day{day:02}_x{scale}"),
+    };
+    if let Some(parent) = std::path::Path::new(&path).parent()
+        && let Err(e) = std::fs::create_dir_all(parent)
+    {
+        println!("Failed to create '{}': {}", parent.display(), e);
+        return;
+    }
+    match std::fs::write(&path, input) {
+        Ok(()) => println!("Generated day {day} input at {scale}x scale in '{path}'"),
+        Err(e) => println!("Failed to write '{path}': {e}"),
+    }
+}
+
+/// Draws a day as an SVG image, on the puzzle input or on the file at `--input`.
+/// Usage: `render <day> [--input <path>] [--output <path>]`
+#[cfg(not(target_arch = "wasm32"))]
+fn render(args: &[String]) {
+    let Some(day) = args.first().and_then(|day| parse_day(day)) else {
+        println!("Usage: render <day> [--input <path>] [--output <path>]");
+        return;
+    };
+    let Some(input) = read_day_input(day, flag_value(args, "--input")) else {
+        return;
+    };
+    let svg = match advent_of_code_2025::render(day, &input) {
+        Ok(svg) => svg,
         Err(e) => {
-            println!("Day {:2} | Failed to read input: {}", day.day, e);
+            println!("Failed to render day {day}: {e}");
             return;
         }
     };
 
-    if let Some(p1) = day.part1 {
-        println!("Day {:2} | Part 1 | {}", day.day, p1(&input));
+    let path = match flag_value(args, "--output") {
+        Some(path) => path.to_string(),
+        None => format!("./day{day:02}.svg"),
+    };
+    match std::fs::write(&path, svg) {
+        Ok(()) => println!("Rendered day {day} in '{path}'"),
+        Err(e) => println!("Failed to write '{path}': {e}"),
     }
-    if let Some(p2) = day.part2 {
-        println!("Day {:2} | Part 2 | {}", day.day, p2(&input));
+}
+
+/// Exports the graph of a day, on the puzzle input or on the file at `--input`.
+/// Usage: `export <day> [--format <dot|graphml|json>] [--from <node>] [--highlight <node=colour,...>] [--input <path>] [--output <path>]`
+#[cfg(not(target_arch = "wasm32"))]
+fn export(args: &[String]) {
+    let Some(day) = args.first().and_then(|day| parse_day(day)) else {
+        println!(
+            "Usage: export <day> [--format <dot|graphml|json>] [--from <node>] [--highlight <node=colour,...>] [--input <path>] [--output <path>]"
+        );
+        return;
+    };
+    let format = flag_value(args, "--format").unwrap_or("dot");
+    let from = flag_value(args, "--from").unwrap_or("you");
+    let highlight =
+        flag_value(args, "--highlight").unwrap_or(advent_of_code_2025::day_11::DEFAULT_HIGHLIGHT);
+    let Some(input) = read_day_input(day, flag_value(args, "--input")) else {
+        return;
+    };
+    let graph = match advent_of_code_2025::export_graph(day, &input, format, from, highlight) {
+        Ok(graph) => graph,
+        Err(e) => {
+            println!("Failed to export day {day}: {e}");
+            return;
+        }
+    };
+
+    let path = match flag_value(args, "--output") {
+        Some(path) => path.to_string(),
+        None => format!("./day{day:02}.{}", format.to_ascii_lowercase()),
+    };
+    match std::fs::write(&path, graph) {
+        Ok(()) => println!("Exported day {day} in '{path}'"),
+        Err(e) => println!("Failed to write '{path}': {e}"),
     }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn run_all() {
-    for day in DAYS {
-        run_day(day);
-        println!("-------+--------+------------------");
+fn current_commit() -> Result<String, String> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err("Failed to get the current commit, please provide a baseline name".to_string());
     }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -0,0 +1,136 @@
+//! Euclidean minimum spanning trees and single linkage clustering, built on a [`KdTree`].
+
+use std::cmp::Reverse;
+
+use crate::disjoint_set::DisjointSet;
+use crate::kd_tree::{Edge, KdTree};
+use crate::point::Point;
+
+/// The edges of a minimum spanning tree, shortest first. The last edge is the one that connects everything.
+/// Ties are broken like [`Edge`] orders them, so it's the same tree that Kruskal's algorithm finds.
+///
+/// Uses Borůvka's algorithm: every round, each component is connected to its closest other
+/// component. That at least halves the number of components, so there are `O(log n)` rounds of
+/// nearest neighbour queries. Going through all pairs by distance instead would take quadratic
+/// time as soon as a single point is far away from the others.
+pub fn euclidean_mst<const N: usize>(points: &[Point<N>]) -> Vec<Edge> {
+    let tree = KdTree::new(points);
+    let mut sets = DisjointSet::new(points.len());
+    let mut edges = Vec::with_capacity(points.len().saturating_sub(1));
+
+    while sets.set_count() > 1 {
+        let labels = tree.labels((0..points.len()).map(|p| sets.find(p)).collect());
+        // The shortest edge leaving each component, by its root
+        let mut shortest: Vec<Option<Edge>> = vec![None; points.len()];
+        for point in 0..points.len() {
+            tree.nearest_other(point, &labels, &mut shortest[sets.find(point)]);
+        }
+        // Two components can pick the same edge, it's only added once
+        for edge in shortest.into_iter().flatten() {
+            if sets.union(edge.a, edge.b) {
+                edges.push(edge);
+            }
+        }
+    }
+    edges.sort_unstable();
+    edges
+}
+
+/// Connects the `k` closest pairs of points, even the ones already in the same cluster.
+/// Returns the point indices of each cluster, largest cluster first.
+pub fn clusters_after<const N: usize>(points: &[Point<N>], k: usize) -> Vec<Vec<usize>> {
+    let tree = KdTree::new(points);
+    let mut sets = DisjointSet::new(points.len());
+    for edge in tree.pairs_by_distance().take(k) {
+        sets.union(edge.a, edge.b);
+    }
+
+    let mut clusters: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+    for point in 0..points.len() {
+        clusters[sets.find(point)].push(point);
+    }
+    clusters.retain(|cluster| !cluster.is_empty());
+    clusters.sort_by_key(|cluster| Reverse(cluster.len()));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_mst() {
+        // Two groups on a line, the gap between them is the longest edge
+        let points = [[0], [10], [1], [12], [3], [11]].map(Point);
+        let mst = euclidean_mst(&points);
+        let edges: Vec<(usize, usize)> = mst.iter().map(|e| (e.a, e.b)).collect();
+        assert_eq!(edges, vec![(0, 2), (1, 5), (3, 5), (2, 4), (1, 4)]);
+        assert_eq!(mst.last().unwrap().distance_squared, 49);
+
+        assert!(euclidean_mst::<2>(&[]).is_empty());
+        assert!(euclidean_mst(&[Point([1, 2])]).is_empty());
+    }
+
+    /// Kruskal's algorithm over every pair
+    fn brute_force<const N: usize>(points: &[Point<N>]) -> Vec<Edge> {
+        let mut pairs = Vec::new();
+        for a in 0..points.len() {
+            for b in a + 1..points.len() {
+                let distance_squared = points[a].distance_squared(&points[b]);
+                pairs.push(Edge {
+                    distance_squared,
+                    a,
+                    b,
+                });
+            }
+        }
+        pairs.sort_unstable();
+        let mut sets = DisjointSet::new(points.len());
+        pairs
+            .into_iter()
+            .filter(|edge| sets.union(edge.a, edge.b))
+            .collect()
+    }
+
+    #[test]
+    fn test_mst_outlier() {
+        // Going through the pairs by distance would visit all 50 million of them before the
+        // outlier is connected
+        let mut points: Vec<Point<3>> = (0..10_000)
+            .map(|i| Point([i % 100, i / 100, (i * 7) % 13]))
+            .collect();
+        points.push(Point([9_000_000; 3]));
+        let mst = euclidean_mst(&points);
+        assert_eq!(mst.len(), 10_000);
+        let last = mst.last().unwrap();
+        assert_eq!(last.b, 10_000);
+        let outlier = points[10_000];
+        let closest = points[..10_000]
+            .iter()
+            .map(|p| p.distance_squared(&outlier))
+            .min();
+        assert_eq!(Some(last.distance_squared), closest);
+    }
+
+    #[test]
+    fn test_clusters_after() {
+        let points = [[0, 0], [10, 10], [0, 1], [10, 11], [50, 50]].map(Point);
+        assert_eq!(
+            clusters_after(&points, 2),
+            vec![vec![0, 2], vec![1, 3], vec![4]]
+        );
+        assert_eq!(clusters_after(&points, 0).len(), 5);
+        assert_eq!(clusters_after(&points, 100), vec![vec![0, 1, 2, 3, 4]]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_mst_matches_kruskal(points in prop::collection::vec(prop::array::uniform3(-20i64..20), 0..80)) {
+            let points: Vec<Point<3>> = points.into_iter().map(Point).collect();
+            prop_assert_eq!(euclidean_mst(&points), brute_force(&points));
+        }
+    }
+}
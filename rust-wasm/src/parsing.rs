@@ -0,0 +1,205 @@
+//! Parsers that the days share, and [`parse_all`] to run a day's parser on the whole input.
+
+use std::fmt;
+use std::ops::Range;
+
+use nom::{
+    IResult, Offset, Parser,
+    branch::alt,
+    character::complete::{self, char, line_ending, multispace1},
+    error::{Error, ErrorKind},
+    multi::{many1, separated_list1},
+};
+
+use crate::grid::{self, Grid};
+
+/// Where and why the input could not be parsed. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// `rest` is the part of `input` where parsing failed
+    fn new(input: &str, rest: &str, kind: Option<ErrorKind>) -> Self {
+        let before = &input[..input.offset(rest)];
+        let line = before.matches('\n').count() + 1;
+        let column = before[before.rfind('\n').map_or(0, |i| i + 1)..]
+            .chars()
+            .count()
+            + 1;
+
+        let found = match rest.chars().next() {
+            Some(c) => format!("{c:?}"),
+            None => "end of input".to_string(),
+        };
+        let message = match kind {
+            Some(ErrorKind::Verify | ErrorKind::MapOpt) => format!("invalid value at {found}"),
+            _ => format!("unexpected {found}"),
+        };
+        ParseError {
+            line,
+            column,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to parse input at line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(error: ParseError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Runs `parser` on the whole input and rejects anything but whitespace after it.
+/// Parsers run in complete mode, so running out of input is an error instead of `Incomplete`.
+pub fn parse_all<'a, T>(
+    mut parser: impl Parser<&'a str, Output = T, Error = Error<&'a str>>,
+    input: &'a str,
+) -> Result<T, ParseError> {
+    match parser.parse_complete(input) {
+        Ok((rest, value)) => {
+            let rest = rest.trim_start();
+            if rest.is_empty() {
+                Ok(value)
+            } else {
+                Err(ParseError::new(input, rest, None))
+            }
+        }
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            Err(ParseError::new(input, e.input, Some(e.code)))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new(input, &input[input.len()..], None)),
+    }
+}
+
+/// One or more items, each on its own line
+pub fn lines<'a, O>(
+    item: impl Parser<&'a str, Output = O, Error = Error<&'a str>>,
+) -> impl Parser<&'a str, Output = Vec<O>, Error = Error<&'a str>> {
+    separated_list1(line_ending, item)
+}
+
+/// One or more items separated by any whitespace, including newlines and empty lines
+pub fn whitespace_separated<'a, O>(
+    item: impl Parser<&'a str, Output = O, Error = Error<&'a str>>,
+) -> impl Parser<&'a str, Output = Vec<O>, Error = Error<&'a str>> {
+    separated_list1(multispace1, item)
+}
+
+/// `N` comma separated numbers, like `162,817,812`
+pub fn coords<const N: usize>(input: &str) -> IResult<&str, [u32; N]> {
+    let mut coords = [0; N];
+    let mut rest = input;
+    for (i, coord) in coords.iter_mut().enumerate() {
+        if i > 0 {
+            (rest, _) = char(',')(rest)?;
+        }
+        (rest, *coord) = complete::u32(rest)?;
+    }
+    Ok((rest, coords))
+}
+
+/// An inclusive range like `11-22`, returned as the half open range `11..23`.
+/// Reversed ranges are rejected.
+pub fn id_range(input: &str) -> IResult<&str, Range<u64>> {
+    (complete::u64, char('-'), complete::u64)
+        .map_opt(|(start, _, end)| (start <= end).then_some(start..end.checked_add(1)?))
+        .parse(input)
+}
+
+/// A single row of on and off cells, like `.##.`
+pub fn cell_row<'a>(
+    on: char,
+    off: char,
+) -> impl Parser<&'a str, Output = Vec<bool>, Error = Error<&'a str>> {
+    many1(alt((char(on).map(|_| true), char(off).map(|_| false))))
+}
+
+/// A rectangular grid of on and off cells, like the `#` and `.` shapes
+pub fn cell_grid<'a>(
+    on: char,
+    off: char,
+) -> impl Parser<&'a str, Output = Grid<bool>, Error = Error<&'a str>> {
+    grid::grid(move |c| match c {
+        c if c == on => Some(true),
+        c if c == off => Some(false),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_all() {
+        let numbers = || lines(complete::u32);
+        assert_eq!(parse_all(numbers(), "1\n2\n3\n\n"), Ok(vec![1, 2, 3]));
+
+        // Trailing garbage is reported where it starts
+        let error = parse_all(numbers(), "1\n2\n3x").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 2));
+        assert_eq!(error.message, "unexpected 'x'");
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse input at line 3, column 2: unexpected 'x'"
+        );
+
+        let error = parse_all(numbers(), "").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(error.message, "unexpected end of input");
+    }
+
+    #[test]
+    fn test_parse_all_trimmed() {
+        // Days trim their input, the position is still relative to the original input
+        let parser = |input: &'static str| lines(coords::<2>).parse(input.trim());
+        let error = parse_all(parser, "\n1,2\n3,4\n5;6\n").unwrap_err();
+        // The list ends before the line that doesn't fit
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(error.message, "unexpected '5'");
+    }
+
+    #[test]
+    fn test_coords() {
+        assert_eq!(coords::<3>("1,2,3"), Ok(("", [1, 2, 3])));
+        assert_eq!(coords::<2>("1,2,3"), Ok((",3", [1, 2])));
+        assert!(coords::<3>("1,2").is_err());
+        assert!(coords::<2>("1,99999999999").is_err());
+    }
+
+    #[test]
+    fn test_id_range() {
+        assert_eq!(id_range("11-22"), Ok(("", 11..23)));
+        assert!(id_range("22-11").is_err());
+        assert!(id_range("1-18446744073709551615").is_err());
+
+        let error = parse_all(id_range, "5-3").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(error.message, "invalid value at '5'");
+    }
+
+    #[test]
+    fn test_cells() {
+        assert_eq!(
+            cell_row('#', '.').parse(".##.]"),
+            Ok(("]", vec![false, true, true, false]))
+        );
+        let grid = parse_all(cell_grid('#', '.'), "#.\n.#").unwrap();
+        assert_eq!(grid.row(1), &[false, true]);
+        assert!(parse_all(cell_grid('#', '.'), "#.\n#").is_err());
+    }
+}
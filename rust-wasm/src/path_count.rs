@@ -0,0 +1,274 @@
+//! Counts the paths between two nodes of a directed acyclic graph, like the devices of day 11.
+//!
+//! A path that visits the waypoints `a` and `b` in this order can only exist if `a` comes before
+//! `b` in the topological order. So out of all orders of the waypoints only the topological one
+//! has to be counted, and the paths through it are the product of the paths of each segment.
+//!
+//! Graphs with a cycle are refused. A path could go around the cycle any number of times, and
+//! only counting simple paths would need a search over every path instead.
+
+use std::collections::VecDeque;
+use std::fmt::Display;
+
+use petgraph::{
+    Direction,
+    algo::{has_path_connecting, toposort},
+    graph::DiGraph,
+    graph::NodeIndex,
+};
+
+pub struct PathCounter<'a, N, E> {
+    graph: &'a DiGraph<N, E>,
+    /// Nodes in topological order
+    order: Vec<NodeIndex>,
+    /// Position of each node in `order`
+    position: Vec<usize>,
+}
+
+impl<'a, N: Display, E> PathCounter<'a, N, E> {
+    /// Fails if the graph has a cycle, because then there could be infinitely many paths. The
+    /// error shows one of the cycles.
+    pub fn new(graph: &'a DiGraph<N, E>) -> Result<Self, String> {
+        let order = toposort(graph, None).map_err(|cycle| {
+            let cycle: Vec<String> = find_cycle(graph, cycle.node_id())
+                .iter()
+                .map(|&node| graph[node].to_string())
+                .collect();
+            format!("The graph has a cycle: {}", cycle.join(" -> "))
+        })?;
+        let mut position = vec![0; graph.node_count()];
+        for (i, node) in order.iter().enumerate() {
+            position[node.index()] = i;
+        }
+        Ok(PathCounter {
+            graph,
+            order,
+            position,
+        })
+    }
+
+    /// Fails with the first target that no path from `from` leads to
+    pub fn check_reachable(&self, from: NodeIndex, targets: &[NodeIndex]) -> Result<(), String> {
+        match targets
+            .iter()
+            .find(|&&to| !has_path_connecting(self.graph, from, to, None))
+        {
+            Some(&to) => Err(format!(
+                "'{}' can't be reached from '{}'",
+                self.graph[to], self.graph[from]
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Number of paths from `from` to `to`
+    pub fn count(&self, from: NodeIndex, to: NodeIndex) -> Result<u128, String> {
+        let (start, end) = (self.position[from.index()], self.position[to.index()]);
+        if start > end {
+            return Ok(0);
+        }
+        Ok(self.forward(start, end)?[end - start])
+    }
+
+    /// Number of paths from `from` to every node, by node index
+    pub fn count_all(&self, from: NodeIndex) -> Result<Vec<u128>, String> {
+        let start = self.position[from.index()];
+        let paths = self.forward(start, self.order.len() - 1)?;
+        let mut by_node = vec![0; self.order.len()];
+        for (node, paths) in self.order[start..].iter().zip(paths) {
+            by_node[node.index()] = paths;
+        }
+        Ok(by_node)
+    }
+
+    /// Paths from the node at position `start` to the nodes at the positions `start..=end`
+    fn forward(&self, start: usize, end: usize) -> Result<Vec<u128>, String> {
+        // Only the nodes between the two in topological order can be on a path
+        let mut paths = vec![0u128; end - start + 1];
+        paths[0] = 1;
+        for i in 0..paths.len() {
+            let count = paths[i];
+            if count == 0 {
+                continue;
+            }
+            let node = self.order[start + i];
+            for next in self.graph.neighbors_directed(node, Direction::Outgoing) {
+                let next = self.position[next.index()];
+                if next <= end {
+                    let next = &mut paths[next - start];
+                    *next = next.checked_add(count).ok_or(OVERFLOW)?;
+                }
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Number of paths from `from` to `to` that visit all `waypoints`, in any order
+    pub fn count_through(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+        waypoints: &[NodeIndex],
+    ) -> Result<u128, String> {
+        let mut waypoints = waypoints.to_vec();
+        waypoints.sort_unstable_by_key(|node| self.position[node.index()]);
+        let mut route = vec![from];
+        route.extend(waypoints);
+        route.push(to);
+        route.dedup();
+
+        route.windows(2).try_fold(1u128, |total, segment| {
+            if total == 0 {
+                return Ok(0);
+            }
+            let paths = self.count(segment[0], segment[1])?;
+            total.checked_mul(paths).ok_or(OVERFLOW.to_string())
+        })
+    }
+}
+
+/// The shortest cycle through `start`, which has to be on one. It starts and ends with `start`.
+fn find_cycle<N, E>(graph: &DiGraph<N, E>, start: NodeIndex) -> Vec<NodeIndex> {
+    let mut previous = vec![None; graph.node_count()];
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for next in graph.neighbors_directed(node, Direction::Outgoing) {
+            if previous[next.index()].is_some() {
+                continue;
+            }
+            previous[next.index()] = Some(node);
+            if next == start {
+                // Walk back to build the cycle
+                let mut cycle = vec![start];
+                let mut node = node;
+                while node != start {
+                    cycle.push(node);
+                    node = previous[node.index()].expect("Every visited node has a previous one");
+                }
+                cycle.push(start);
+                cycle.reverse();
+                return cycle;
+            }
+            queue.push_back(next);
+        }
+    }
+    vec![start]
+}
+
+const OVERFLOW: &str = "The number of paths does not fit into 128 bits";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use proptest::prelude::*;
+
+    fn graph(nodes: usize, edges: &[(u32, u32)]) -> DiGraph<usize, ()> {
+        let mut graph = DiGraph::new();
+        for node in 0..nodes {
+            graph.add_node(node);
+        }
+        graph.extend_with_edges(edges);
+        graph
+    }
+
+    /// Follows every path
+    fn brute_force(
+        graph: &DiGraph<usize, ()>,
+        from: usize,
+        to: usize,
+        waypoints: &[usize],
+    ) -> u128 {
+        let mut total = 0;
+        let mut stack = vec![vec![from]];
+        while let Some(path) = stack.pop() {
+            let last = *path.last().unwrap();
+            if last == to && waypoints.iter().all(|w| path.contains(w)) {
+                total += 1;
+            }
+            for next in graph.neighbors(NodeIndex::new(last)) {
+                let mut longer = path.clone();
+                longer.push(next.index());
+                stack.push(longer);
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn test_count_through() {
+        // 0 -> 1 -> 3 -> 4 and 0 -> 2 -> 3 -> 4, with a shortcut 0 -> 3
+        let g = graph(5, &[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (0, 3)]);
+        let counter = PathCounter::new(&g).unwrap();
+        let n = NodeIndex::new;
+        assert_eq!(counter.count(n(0), n(4)), Ok(3));
+        assert_eq!(counter.count(n(4), n(0)), Ok(0));
+        assert_eq!(counter.count_all(n(1)), Ok(vec![0, 1, 0, 1, 1]));
+        assert_eq!(counter.count_through(n(0), n(4), &[n(3), n(1)]), Ok(1));
+        // 1 and 2 are never on the same path
+        assert_eq!(counter.count_through(n(0), n(4), &[n(1), n(2)]), Ok(0));
+        assert_eq!(counter.count_through(n(0), n(4), &[]), Ok(3));
+        assert_eq!(counter.count_through(n(1), n(4), &[n(0)]), Ok(0));
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Every diamond doubles the number of paths
+        let edges: Vec<(u32, u32)> = (0..130)
+            .flat_map(|i| [(3 * i, 3 * i + 1), (3 * i, 3 * i + 2)])
+            .chain((0..130).flat_map(|i| [(3 * i + 1, 3 * i + 3), (3 * i + 2, 3 * i + 3)]))
+            .collect();
+        let g = graph(391, &edges);
+        let counter = PathCounter::new(&g).unwrap();
+        let n = NodeIndex::new;
+        assert_eq!(counter.count(n(0), n(3 * 127)), Ok(1 << 127));
+        assert!(counter.count(n(0), n(390)).is_err());
+    }
+
+    #[test]
+    fn test_cycle() {
+        let g = graph(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (3, 1)]);
+        let Err(e) = PathCounter::new(&g) else {
+            panic!("The cycle was not found");
+        };
+        // The cycle can start at any of its nodes, depending on where the sort stopped
+        assert!(
+            ["1 -> 2 -> 3 -> 1", "2 -> 3 -> 1 -> 2", "3 -> 1 -> 2 -> 3"]
+                .iter()
+                .any(|cycle| e == format!("The graph has a cycle: {cycle}")),
+            "{e}"
+        );
+    }
+
+    #[test]
+    fn test_check_reachable() {
+        let g = graph(4, &[(0, 1), (1, 2)]);
+        let counter = PathCounter::new(&g).unwrap();
+        let n = NodeIndex::new;
+        assert_eq!(counter.check_reachable(n(0), &[n(1), n(2)]), Ok(()));
+        assert_eq!(
+            counter.check_reachable(n(0), &[n(2), n(3)]),
+            Err("'3' can't be reached from '0'".to_string())
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest! {
+        #[test]
+        fn prop_matches_brute_force(
+            edges in prop::collection::vec((0u32..9, 0u32..9), 0..25),
+            waypoints in prop::collection::vec(0usize..9, 0..3),
+            from in 0usize..9,
+        ) {
+            // Edges only go to higher nodes, so there are no cycles
+            let edges: Vec<_> = edges.into_iter().filter(|(a, b)| a < b).collect();
+            let g = graph(9, &edges);
+            let counter = PathCounter::new(&g).unwrap();
+            let nodes: Vec<_> = waypoints.iter().map(|&w| NodeIndex::new(w)).collect();
+            prop_assert_eq!(
+                counter.count_through(NodeIndex::new(from), NodeIndex::new(8), &nodes),
+                Ok(brute_force(&g, from, 8, &waypoints))
+            );
+        }
+    }
+}
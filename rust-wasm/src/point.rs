@@ -0,0 +1,95 @@
+//! An integer point with any number of dimensions, like the 3D junction boxes of day 8.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<const N: usize>(pub [i64; N]);
+
+impl<const N: usize> Point<N> {
+    pub fn new(coords: [i64; N]) -> Self {
+        Point(coords)
+    }
+
+    pub fn coords(&self) -> &[i64; N] {
+        &self.0
+    }
+
+    /// Squared euclidean distance. It's exact, so comparing distances has no float rounding issues.
+    /// Only differences close to the whole `i64` range don't fit, then it saturates.
+    pub fn distance_squared(&self, other: &Self) -> u128 {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(a, b)| u128::from(a.abs_diff(*b)).pow(2))
+            .fold(0, u128::saturating_add)
+    }
+
+    /// Sum of the absolute differences of the coordinates
+    pub fn manhattan_distance(&self, other: &Self) -> u128 {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(a, b)| u128::from(a.abs_diff(*b)))
+            .sum()
+    }
+}
+
+impl<const N: usize> From<[i64; N]> for Point<N> {
+    fn from(coords: [i64; N]) -> Self {
+        Point(coords)
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &i64 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut i64 {
+        &mut self.0[axis]
+    }
+}
+
+/// Prints the coordinates comma separated, like the puzzle inputs
+impl<const N: usize> fmt::Display for Point<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, coord) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{coord}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> fmt::Debug for Point<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({self})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new([162, 817, 812]);
+        let b = Point::new([425, 690, 689]);
+        assert_eq!(a.distance_squared(&b), 263 * 263 + 127 * 127 + 123 * 123);
+        assert_eq!(a.manhattan_distance(&b), 263 + 127 + 123);
+        assert_eq!(b[0], 425);
+        assert_eq!(a.to_string(), "162,817,812");
+
+        // Doesn't overflow at the extremes
+        let (min, max) = (Point([i64::MIN; 4]), Point([i64::MAX; 4]));
+        assert_eq!(min.distance_squared(&max), u128::MAX);
+        assert_eq!(min.manhattan_distance(&max), 4 * u128::from(u64::MAX));
+    }
+}
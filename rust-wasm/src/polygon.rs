@@ -0,0 +1,226 @@
+//! A polygon with axis aligned edges on a grid of tiles, like the red and green tiles of day 9.
+//!
+//! The vertices are tiles, the polygon covers every tile on its edges and inside of it.
+//! Coordinates are compressed: every x that has a vertex gets its own column, and the tiles
+//! between two of them share one column. Rows work the same way. A prefix sum over the covered
+//! compressed cells then answers if a rectangle is covered in constant time.
+
+use crate::grid::Grid;
+use crate::point::Point;
+
+pub struct RectilinearPolygon {
+    /// First x of each compressed column, the column ends where the next one starts
+    columns: Vec<i64>,
+    /// First y of each compressed row
+    rows: Vec<i64>,
+    /// Number of covered cells above and left of each cell, with a row and column of zeros in front
+    covered: Grid<u32>,
+}
+
+impl RectilinearPolygon {
+    /// The vertices are in order, the last one connects back to the first one.
+    /// Fails if an edge is neither horizontal nor vertical.
+    pub fn new(vertices: &[Point<2>]) -> Result<Self, String> {
+        let edges = || {
+            vertices
+                .iter()
+                .zip(vertices.iter().cycle().skip(1))
+                .take(vertices.len())
+        };
+        if let Some((a, b)) = edges().find(|(a, b)| a[0] != b[0] && a[1] != b[1]) {
+            return Err(format!("The edge from {a} to {b} is not axis aligned"));
+        }
+        if vertices.is_empty() {
+            return Err("The polygon has no vertices".to_string());
+        }
+
+        let columns = compress(vertices.iter().map(|v| v[0]));
+        let rows = compress(vertices.iter().map(|v| v[1]));
+        let index = |values: &[i64], value: i64| values.partition_point(|&v| v <= value) - 1;
+        let cell = |v: &Point<2>| (index(&rows, v[1]), index(&columns, v[0]));
+
+        // The edges themselves
+        let mut inside = Grid::filled(rows.len(), columns.len(), false);
+        for (a, b) in edges() {
+            let ((r1, c1), (r2, c2)) = (cell(a), cell(b));
+            for row in r1.min(r2)..=r1.max(r2) {
+                for col in c1.min(c2)..=c1.max(c2) {
+                    inside[(row, col)] = true;
+                }
+            }
+        }
+
+        // Everything between the edges. A ray to the left crosses the vertical edges that span the
+        // row, the bottom end doesn't count so vertices are only counted once.
+        for (row, &y) in rows.iter().enumerate() {
+            let mut crossings: Vec<usize> = edges()
+                .filter(|(a, b)| a[0] == b[0] && a[1].min(b[1]) <= y && y < a[1].max(b[1]))
+                .map(|(a, _)| cell(a).1)
+                .collect();
+            crossings.sort_unstable();
+            for pair in crossings.chunks_exact(2) {
+                for col in pair[0]..=pair[1] {
+                    inside[(row, col)] = true;
+                }
+            }
+        }
+
+        let mut covered = Grid::filled(rows.len() + 1, columns.len() + 1, 0u32);
+        for row in 0..rows.len() {
+            for col in 0..columns.len() {
+                covered[(row + 1, col + 1)] = u32::from(inside[(row, col)])
+                    + covered[(row, col + 1)]
+                    + covered[(row + 1, col)]
+                    - covered[(row, col)];
+            }
+        }
+
+        Ok(RectilinearPolygon {
+            columns,
+            rows,
+            covered,
+        })
+    }
+
+    /// Compressed (row, column) of a tile, if it's inside the bounding box
+    fn cell(&self, tile: &Point<2>) -> Option<(usize, usize)> {
+        let index = |values: &[i64], value: i64| {
+            let (&first, &last) = (values.first()?, values.last()?);
+            (first..=last)
+                .contains(&value)
+                .then(|| values.partition_point(|&v| v <= value) - 1)
+        };
+        Some((index(&self.rows, tile[1])?, index(&self.columns, tile[0])?))
+    }
+
+    /// Is the tile on an edge or inside the polygon
+    pub fn contains(&self, tile: &Point<2>) -> bool {
+        self.cell(tile)
+            .is_some_and(|cell| self.covered_cells(cell, cell) == 1)
+    }
+
+    /// Is every tile of the rectangle with the opposite corners `a` and `b` covered by the polygon
+    pub fn contains_rect(&self, a: &Point<2>, b: &Point<2>) -> bool {
+        let (Some((r1, c1)), Some((r2, c2))) = (self.cell(a), self.cell(b)) else {
+            return false;
+        };
+        let (top_left, bottom_right) = ((r1.min(r2), c1.min(c2)), (r1.max(r2), c1.max(c2)));
+        let cells = (bottom_right.0 - top_left.0 + 1) * (bottom_right.1 - top_left.1 + 1);
+        self.covered_cells(top_left, bottom_right) as usize == cells
+    }
+
+    /// Number of covered compressed cells in the inclusive range
+    fn covered_cells(&self, (r1, c1): (usize, usize), (r2, c2): (usize, usize)) -> u32 {
+        let covered = &self.covered;
+        covered[(r2 + 1, c2 + 1)] + covered[(r1, c1)]
+            - covered[(r1, c2 + 1)]
+            - covered[(r2 + 1, c1)]
+    }
+}
+
+/// Every value gets its own slot, followed by one slot for the gap up to the next value
+fn compress(values: impl Iterator<Item = i64>) -> Vec<i64> {
+    let mut values: Vec<i64> = values.collect();
+    values.sort_unstable();
+    values.dedup();
+
+    let mut slots = Vec::with_capacity(values.len() * 2);
+    for (i, &value) in values.iter().enumerate() {
+        slots.push(value);
+        if values.get(i + 1).is_some_and(|&next| next > value + 1) {
+            slots.push(value + 1);
+        }
+    }
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[[i64; 2]]) -> RectilinearPolygon {
+        let vertices: Vec<Point<2>> = vertices.iter().copied().map(Point).collect();
+        RectilinearPolygon::new(&vertices).unwrap()
+    }
+
+    /// The example of day 9
+    fn example() -> RectilinearPolygon {
+        polygon(&[
+            [7, 1],
+            [11, 1],
+            [11, 7],
+            [9, 7],
+            [9, 5],
+            [2, 5],
+            [2, 3],
+            [7, 3],
+        ])
+    }
+
+    #[test]
+    fn test_contains() {
+        let polygon = example();
+        let tiles = (0..9)
+            .map(|y| {
+                (0..13)
+                    .map(|x| {
+                        if polygon.contains(&Point([x, y])) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            tiles,
+            ".............
+.......#####.
+.......#####.
+..##########.
+..##########.
+..##########.
+.........###.
+.........###.
+............."
+        );
+    }
+
+    #[test]
+    fn test_contains_rect() {
+        let polygon = example();
+        assert!(polygon.contains_rect(&Point([9, 5]), &Point([2, 3])));
+        assert!(polygon.contains_rect(&Point([7, 1]), &Point([11, 5])));
+        assert!(!polygon.contains_rect(&Point([7, 1]), &Point([2, 5])));
+        assert!(!polygon.contains_rect(&Point([11, 1]), &Point([2, 5])));
+        assert!(!polygon.contains_rect(&Point([0, 0]), &Point([2, 3])));
+    }
+
+    #[test]
+    fn test_holes_between_vertices() {
+        // A U shape, the gap in the middle is wider than one tile
+        let polygon = polygon(&[
+            [0, 0],
+            [2, 0],
+            [2, 5],
+            [8, 5],
+            [8, 0],
+            [10, 0],
+            [10, 9],
+            [0, 9],
+        ]);
+        assert!(!polygon.contains(&Point([5, 2])));
+        assert!(polygon.contains(&Point([5, 7])));
+        assert!(!polygon.contains_rect(&Point([0, 0]), &Point([10, 9])));
+        assert!(polygon.contains_rect(&Point([0, 5]), &Point([10, 9])));
+    }
+
+    #[test]
+    fn test_diagonal_edge() {
+        let vertices = [[0, 0], [5, 0], [0, 5]].map(Point);
+        assert!(RectilinearPolygon::new(&vertices).is_err());
+        assert!(RectilinearPolygon::new(&[]).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! Shapes made of square cells, like the presents of day 12, and a board to place them on.
+//!
+//! A polyomino is stored as its sorted cells, moved so the top and left most cells are in row and
+//! column 0. Two shapes are the same if they have the same cells, no matter where they came from.
+
+use nom::{Parser, combinator::map_opt, error::Error};
+
+use crate::grid::Grid;
+use crate::parsing::cell_grid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Polyomino {
+    /// `(row, col)`, sorted
+    cells: Vec<(usize, usize)>,
+}
+
+impl Polyomino {
+    /// Returns `None` if there are no cells
+    pub fn new(cells: impl IntoIterator<Item = (usize, usize)>) -> Option<Self> {
+        let cells: Vec<_> = cells.into_iter().collect();
+        let top = cells.iter().map(|&(row, _)| row).min()?;
+        let left = cells.iter().map(|&(_, col)| col).min()?;
+        let mut cells: Vec<_> = cells
+            .into_iter()
+            .map(|(row, col)| (row - top, col - left))
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        Some(Polyomino { cells })
+    }
+
+    /// The set cells of the grid. Returns `None` if there are none.
+    pub fn from_grid(grid: &Grid<bool>) -> Option<Self> {
+        Self::new(grid.positions().filter(|(_, on)| **on).map(|(pos, _)| pos))
+    }
+
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.iter().map(|&(row, _)| row).max().unwrap_or(0) + 1
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.iter().map(|&(_, col)| col).max().unwrap_or(0) + 1
+    }
+
+    /// `(height, width)` of the smallest rectangle around the shape
+    pub fn bounding_box(&self) -> (usize, usize) {
+        (self.height(), self.width())
+    }
+
+    pub fn rotate_cw(&self) -> Self {
+        let height = self.height();
+        Self::new(self.cells.iter().map(|&(row, col)| (col, height - 1 - row)))
+            .expect("Rotating keeps the cells")
+    }
+
+    /// Mirrors the shape left to right
+    pub fn flip(&self) -> Self {
+        let width = self.width();
+        Self::new(self.cells.iter().map(|&(row, col)| (row, width - 1 - col)))
+            .expect("Flipping keeps the cells")
+    }
+
+    /// The different rotations and reflections, sorted. Between 1 and 8 of them, symmetric shapes
+    /// have fewer.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations = Vec::with_capacity(8);
+        let mut shape = self.clone();
+        for _ in 0..4 {
+            orientations.push(shape.flip());
+            shape = shape.rotate_cw();
+            orientations.push(shape.clone());
+        }
+        orientations.sort_unstable();
+        orientations.dedup();
+        orientations
+    }
+
+    /// The same for all rotations and reflections of a shape
+    pub fn canonical(&self) -> Self {
+        self.orientations()
+            .into_iter()
+            .next()
+            .expect("There is always at least one orientation")
+    }
+
+    /// One bit mask per row, bit `col` is set for every cell. `None` if the shape is wider than
+    /// 64 cells.
+    pub fn row_masks(&self) -> Option<Vec<u64>> {
+        if self.width() > 64 {
+            return None;
+        }
+        let mut masks = vec![0; self.height()];
+        for &(row, col) in &self.cells {
+            masks[row] |= 1 << col;
+        }
+        Some(masks)
+    }
+}
+
+/// A polyomino from a block of `on` and `off` characters
+pub fn polyomino<'a>(
+    on: char,
+    off: char,
+) -> impl Parser<&'a str, Output = Polyomino, Error = Error<&'a str>> {
+    map_opt(cell_grid(on, off), |grid| Polyomino::from_grid(&grid))
+}
+
+/// A rectangle of at most 64 columns, every row is one bit mask
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    width: usize,
+    rows: Vec<u64>,
+}
+
+impl Board {
+    /// Returns `None` if the board is wider than 64 cells
+    pub fn new(height: usize, width: usize) -> Option<Self> {
+        (width <= 64).then(|| Board {
+            width,
+            rows: vec![0; height],
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn filled_cells(&self) -> usize {
+        self.rows.iter().map(|row| row.count_ones() as usize).sum()
+    }
+
+    /// The masks of the shape moved to `left`, if the shape is inside the board there
+    fn masks_at(&self, shape: &Polyomino, (top, left): (usize, usize)) -> Option<Vec<u64>> {
+        let (height, width) = shape.bounding_box();
+        let inside = |start: usize, len: usize, limit: usize| {
+            start.checked_add(len).is_some_and(|end| end <= limit)
+        };
+        if !inside(top, height, self.height()) || !inside(left, width, self.width) {
+            return None;
+        }
+        // The board is at most 64 wide, so the shape is too and `left` is below 64
+        let masks = shape.row_masks()?;
+        Some(masks.into_iter().map(|mask| mask << left).collect())
+    }
+
+    /// Is the shape inside the board and are all of its cells free?
+    pub fn fits(&self, shape: &Polyomino, position: (usize, usize)) -> bool {
+        self.masks_at(shape, position).is_some_and(|masks| {
+            masks
+                .iter()
+                .zip(&self.rows[position.0..])
+                .all(|(mask, row)| mask & row == 0)
+        })
+    }
+
+    /// Fills the cells of the shape. Returns false and leaves the board as it is if it doesn't fit.
+    pub fn place(&mut self, shape: &Polyomino, position: (usize, usize)) -> bool {
+        if !self.fits(shape, position) {
+            return false;
+        }
+        self.apply(shape, position, |row, mask| *row |= mask)
+    }
+
+    /// Clears the cells of a shape that was placed before. Returns false and leaves the board as
+    /// it is if the shape is not inside the board.
+    pub fn remove(&mut self, shape: &Polyomino, position: (usize, usize)) -> bool {
+        self.apply(shape, position, |row, mask| *row &= !mask)
+    }
+
+    fn apply(
+        &mut self,
+        shape: &Polyomino,
+        position: (usize, usize),
+        change: impl Fn(&mut u64, u64),
+    ) -> bool {
+        let Some(masks) = self.masks_at(shape, position) else {
+            return false;
+        };
+        for (mask, row) in masks.into_iter().zip(&mut self.rows[position.0..]) {
+            change(row, mask);
+        }
+        true
+    }
+
+    /// Top left positions where the shape fits
+    pub fn placements<'a>(
+        &'a self,
+        shape: &'a Polyomino,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let (height, width) = shape.bounding_box();
+        let rows = (self.height() + 1).saturating_sub(height);
+        let cols = (self.width + 1).saturating_sub(width);
+        (0..rows)
+            .flat_map(move |top| (0..cols).map(move |left| (top, left)))
+            .filter(|&pos| self.fits(shape, pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse_all;
+
+    fn shape(input: &str) -> Polyomino {
+        parse_all(polyomino('#', '.'), input).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let l = shape("#..\n#..\n##.");
+        assert_eq!(l.cells(), &[(0, 0), (1, 0), (2, 0), (2, 1)]);
+        assert_eq!(l.cell_count(), 4);
+        assert_eq!(l.bounding_box(), (3, 2));
+        assert!(parse_all(polyomino('#', '.'), "...\n...").is_err());
+    }
+
+    #[test]
+    fn test_orientations() {
+        let l = shape("#.\n#.\n##");
+        assert_eq!(l.orientations().len(), 8);
+        assert_eq!(l.rotate_cw(), shape("###\n#.."));
+        assert_eq!(l.flip(), shape(".#\n.#\n##"));
+        assert_eq!(l.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), l);
+
+        assert_eq!(shape("##\n##").orientations().len(), 1);
+        assert_eq!(shape("###").orientations().len(), 2);
+        assert_eq!(shape(".#.\n###").orientations().len(), 4);
+        assert_eq!(shape("##.\n.##").orientations().len(), 4);
+
+        // Every orientation has the same canonical form
+        for orientation in l.orientations() {
+            assert_eq!(orientation.canonical(), l.canonical());
+        }
+        assert_ne!(l.canonical(), shape("##.\n.##").canonical());
+    }
+
+    #[test]
+    fn test_wide_shape() {
+        let wide = Polyomino::new((0..70).map(|col| (0, col))).unwrap();
+        assert_eq!(wide.row_masks(), None);
+        assert_eq!(shape("#.#\n###").row_masks(), Some(vec![0b101, 0b111]));
+        let board = Board::new(2, 64).unwrap();
+        assert!(!board.fits(&wide, (0, 0)));
+        assert_eq!(board.placements(&wide).count(), 0);
+    }
+
+    #[test]
+    fn test_board() {
+        let mut board = Board::new(3, 3).unwrap();
+        let l = shape("#.\n#.\n##");
+        assert_eq!(board.placements(&l).count(), 2);
+        assert!(board.place(&l, (0, 0)));
+        assert_eq!(board.filled_cells(), 4);
+        assert!(!board.place(&l, (0, 0)));
+        assert!(!board.place(&shape("##\n#.\n#."), (0, 1)));
+        assert!(board.place(&shape("##\n.#"), (0, 1)));
+        assert_eq!(board.filled_cells(), 7);
+
+        assert!(board.remove(&l, (0, 0)));
+        assert_eq!(board.filled_cells(), 3);
+        // Outside of the board nothing happens
+        assert!(!board.remove(&l, (1, 2)));
+        assert!(!board.remove(&l, (usize::MAX, 0)));
+        assert_eq!(board.filled_cells(), 3);
+        assert!(!board.fits(&l, (1, 0)));
+        assert!(Board::new(1, 65).is_none());
+    }
+}
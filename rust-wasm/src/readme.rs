@@ -0,0 +1,239 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{Day, bench_compare};
+
+pub const TABLE_START: &str = "<!-- BENCHMARK_TABLE_START -->";
+pub const TABLE_END: &str = "<!-- BENCHMARK_TABLE_END -->";
+
+/// Benchmark times in milliseconds, keyed by day number
+pub type Timings = HashMap<u32, f64>;
+
+/// Reads the mean of every `dayXX_combined` benchmark from criterion's output directory
+pub fn read_criterion(criterion_dir: &Path) -> Result<Timings, String> {
+    Ok(bench_compare::read_criterion(criterion_dir)?
+        .into_iter()
+        .filter_map(|(day, timings)| Some((day, timings.combined?)))
+        .collect())
+}
+
+/// Parses the text output of `cargo bench --target wasm32-unknown-unknown`.
+///
+/// Criterion prints `dayXX_combined  time: [low mid high]`, but puts the name on its own line
+/// if it is too long. So we remember the last benchmark name we have seen.
+pub fn parse_wasm_output(output: &str) -> Timings {
+    let mut timings = Timings::new();
+    let mut current_day = None;
+
+    for line in output.lines() {
+        if let Some(day) = line.split_whitespace().find_map(combined_day) {
+            current_day = Some(day);
+        }
+
+        let Some((_, times)) = line.split_once("time:") else {
+            continue;
+        };
+        let Some(day) = current_day.take() else {
+            continue;
+        };
+
+        // The middle value is the estimate
+        let values = times
+            .trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        if values.len() != 6 {
+            continue;
+        }
+        if let Some(ms) = to_millis(values[2], values[3]) {
+            timings.insert(day, ms);
+        }
+    }
+    timings
+}
+
+fn combined_day(name: &str) -> Option<u32> {
+    name.strip_prefix("day")?
+        .strip_suffix("_combined")?
+        .parse()
+        .ok()
+}
+
+fn to_millis(value: &str, unit: &str) -> Option<f64> {
+    let value: f64 = value.parse().ok()?;
+    let factor = match unit {
+        "ps" => 1e-9,
+        "ns" => 1e-6,
+        "µs" | "us" => 1e-3,
+        "ms" => 1.0,
+        "s" => 1e3,
+        _ => return None,
+    };
+    Some(value * factor)
+}
+
+/// Generates the markdown benchmark table. Missing timings are shown as `-`.
+pub fn benchmark_table(days: &[&Day], native: &Timings, wasm: &Timings) -> String {
+    let header = ["Day", "Stars", "Code", "Native", "WebAssembly"];
+    let rows = days
+        .iter()
+        .map(|day| {
+            let stars = [day.part1.is_some(), day.part2.is_some()]
+                .iter()
+                .filter(|&&implemented| implemented)
+                .map(|_| ":star:")
+                .collect::<Vec<_>>()
+                .join(" ");
+            [
+                format!(
+                    "[Day {:02}: {}](https://adventofcode.com/2025/day/{})",
+                    day.day,
+                    day.title(),
+                    day.day
+                ),
+                stars,
+                format!("[Code](rust-wasm/src/day_{:02}.rs)", day.day),
+                format_time(native.get(&day.day)),
+                format_time(wasm.get(&day.day)),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    // Pad every column to the widest cell, like a markdown formatter would
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| row[col].chars().count())
+                .chain(std::iter::once(header[col].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut table = String::new();
+    let header_cells = header
+        .iter()
+        .enumerate()
+        .map(|(col, cell)| align_cell(cell, widths[col], col))
+        .collect::<Vec<_>>();
+    table.push_str(&format!("| {} |\n", header_cells.join(" | ")));
+
+    let separators = widths
+        .iter()
+        .enumerate()
+        .map(|(col, &width)| match col {
+            0 | 1 => format!(":{}", "-".repeat(width - 1)),
+            2 => format!(":{}:", "-".repeat(width - 2)),
+            _ => format!("{}:", "-".repeat(width - 1)),
+        })
+        .collect::<Vec<_>>();
+    table.push_str(&format!("| {} |\n", separators.join(" | ")));
+
+    for row in rows {
+        let cells = row
+            .iter()
+            .enumerate()
+            .map(|(col, cell)| align_cell(cell, widths[col], col))
+            .collect::<Vec<_>>();
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    table
+}
+
+fn align_cell(cell: &str, width: usize, col: usize) -> String {
+    match col {
+        0 | 1 => format!("{cell:<width$}"),
+        2 => format!("{cell:^width$}"),
+        _ => format!("{cell:>width$}"),
+    }
+}
+
+fn format_time(ms: Option<&f64>) -> String {
+    match ms {
+        Some(ms) => format!("{ms:.3} ms"),
+        None => "-".to_string(),
+    }
+}
+
+/// Replaces everything between the table markers with the new table
+pub fn replace_table(readme: &str, table: &str) -> Result<String, String> {
+    let start = readme
+        .find(TABLE_START)
+        .ok_or(format!("Marker '{TABLE_START}' not found in README"))?
+        + TABLE_START.len();
+    let end = readme[start..]
+        .find(TABLE_END)
+        .ok_or(format!("Marker '{TABLE_END}' not found in README"))?
+        + start;
+
+    Ok(format!(
+        "{}\n\n{}\n{}",
+        &readme[..start],
+        table,
+        &readme[end..]
+    ))
+}
+
+pub fn update_readme(path: &Path, table: &str) -> Result<(), String> {
+    let readme = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    let updated = replace_table(&readme, table)?;
+    std::fs::write(path, updated)
+        .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static WASM_OUTPUT: &str = "
+day01_combined          time:   [128.10 µs 129.02 µs 130.50 µs]
+day08_combined
+                        time:   [62.103 ms 62.698 ms 63.301 ms]
+day12_combined          time:   [bad]
+";
+
+    #[test]
+    fn test_parse_wasm_output() {
+        let timings = parse_wasm_output(WASM_OUTPUT);
+        assert_eq!(timings.len(), 2);
+        assert!((timings[&1] - 0.12902).abs() < 1e-9);
+        assert!((timings[&8] - 62.698).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_benchmark_table() {
+        let days = [&crate::day_01::SOLUTION];
+        let native = Timings::from([(1, 0.0771)]);
+        let table = benchmark_table(&days, &native, &Timings::new());
+
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(
+            lines[2].contains("[Day 01: Secret Entrance](https://adventofcode.com/2025/day/1)")
+        );
+        assert!(lines[2].contains(":star: :star:"));
+        assert!(lines[2].contains("0.077 ms"));
+        assert!(lines[2].ends_with(" - |"));
+        // All lines have the same width
+        assert!(
+            lines
+                .iter()
+                .all(|l| l.chars().count() == lines[0].chars().count())
+        );
+    }
+
+    #[test]
+    fn test_replace_table() {
+        let readme = format!("intro\n{TABLE_START}\nold\n{TABLE_END}\noutro");
+        let replaced = replace_table(&readme, "| new |\n").unwrap();
+        assert_eq!(
+            replaced,
+            format!("intro\n{TABLE_START}\n\n| new |\n\n{TABLE_END}\noutro")
+        );
+        // Replacing twice is stable
+        assert_eq!(replace_table(&replaced, "| new |\n").unwrap(), replaced);
+        assert!(replace_table("no markers", "").is_err());
+    }
+}
@@ -0,0 +1,55 @@
+//! The counting allocator is installed for this test binary only, so no other test allocates at
+//! the same time and the counts are exact.
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::alloc::{GlobalAlloc, Layout};
+
+use advent_of_code_2025::alloc_profile::{AllocStats, CountingAllocator, measure};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// A single test, because tests in the same binary run in parallel and would count each other
+#[test]
+fn test_measure() {
+    let (sum, stats) = measure(|| {
+        let big = vec![1u8; 100_000];
+        let sum = big.iter().map(|&b| b as u64).sum::<u64>();
+        drop(big);
+        let small = vec![1u8; 1_000];
+        sum + small.len() as u64
+    });
+    assert_eq!(sum, 101_000);
+    assert_eq!(
+        stats,
+        AllocStats {
+            allocations: 2,
+            allocated_bytes: 101_000,
+            peak_live_bytes: 100_000,
+        }
+    );
+
+    // Growing counts both blocks at the peak, the old one is freed after copying
+    let (_, stats) = measure(|| {
+        let mut v = Vec::<u8>::with_capacity(1_000);
+        v.reserve_exact(2_000);
+        v
+    });
+    assert_eq!(stats.allocations, 2);
+    assert_eq!(stats.allocated_bytes, 3_000);
+    assert_eq!(stats.peak_live_bytes, 3_000);
+
+    // An alloc or a realloc that fails doesn't change the counts
+    let huge = Layout::from_size_align(isize::MAX as usize - 4096, 8).unwrap();
+    let (ptr, stats) = measure(|| unsafe { GLOBAL.alloc(huge) });
+    assert!(ptr.is_null());
+    assert_eq!(stats, AllocStats::default());
+
+    let layout = Layout::from_size_align(16, 8).unwrap();
+    let ptr = unsafe { std::alloc::alloc(layout) };
+    let (new_ptr, stats) =
+        measure(|| unsafe { GLOBAL.realloc(ptr, layout, isize::MAX as usize - 4096) });
+    assert!(new_ptr.is_null());
+    assert_eq!(stats, AllocStats::default());
+    unsafe { std::alloc::dealloc(ptr, layout) };
+}